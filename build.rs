@@ -0,0 +1,141 @@
+// Generate the code that depends on which files there are:
+//
+// - A module for each day: every src/dayNN.rs is declared in lib.rs, and the solution it
+//   registers (with `register!`) goes in the registry.
+// - A test for each part of each example: every input in inputs/<day>/ with an `.expected` file
+//   beside it gets one test per part that the file gives an answer for.  The tests themselves are
+//   included by src/examples.rs.
+// - With the embed-inputs feature, a table of every file in inputs/, for src/helpers.rs.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=inputs");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    day_modules(&out_dir);
+    example_tests(&out_dir);
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        embedded_inputs(&out_dir);
+    }
+}
+
+// Every file in inputs/ as `(path relative to inputs/, include_str!(...))`
+fn embedded_inputs(out_dir: &Path) {
+    let inputs = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("inputs");
+    let mut files = Vec::new();
+    let mut dirs = vec![inputs.clone()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).expect("Can't read an inputs directory") {
+            let path = entry.expect("Can't read an inputs directory").path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+
+    let mut table = String::from("&[\n");
+    for path in files {
+        let relative: Vec<_> = path
+            .strip_prefix(&inputs)
+            .unwrap()
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        writeln!(table, "    ({:?}, include_str!({:?})),", relative.join("/"), path.display().to_string()).unwrap();
+    }
+    table.push_str("]\n");
+
+    fs::write(out_dir.join("embedded_inputs.rs"), table).expect("Can't write the embedded inputs");
+}
+
+// The day modules, for lib.rs, and the list of their solutions, for registry.rs
+fn day_modules(out_dir: &Path) {
+    let src = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    let mut modules: Vec<String> = fs::read_dir(&src)
+        .expect("Can't read the src directory")
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let module = name.strip_suffix(".rs")?;
+            let day = module.strip_prefix("day")?;
+            (day.len() == 2 && day.bytes().all(|b| b.is_ascii_digit())).then(|| module.to_owned())
+        })
+        .collect();
+    modules.sort();
+
+    let mut declarations = String::new();
+    let mut solutions = String::from("&[\n");
+    for module in modules {
+        let path = src.join(format!("{}.rs", module));
+        writeln!(declarations, "#[path = {:?}]\npub mod {};", path.display().to_string(), module).unwrap();
+        writeln!(solutions, "    crate::{}::VARIANTS,", module).unwrap();
+    }
+    solutions.push_str("]\n");
+
+    fs::write(out_dir.join("day_modules.rs"), declarations).expect("Can't write the day modules");
+    fs::write(out_dir.join("solutions.rs"), solutions).expect("Can't write the list of solutions");
+}
+
+fn example_tests(out_dir: &Path) {
+    let inputs = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("inputs");
+    let mut tests = String::new();
+    let mut days: Vec<_> = fs::read_dir(&inputs)
+        .expect("Can't read the inputs directory")
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let day: usize = path.file_name()?.to_str()?.parse().ok()?;
+            Some((day, path))
+        })
+        .collect();
+    days.sort();
+
+    for (day, dir) in days {
+        let mut cases: Vec<_> = fs::read_dir(&dir)
+            .expect("Can't read a day's inputs directory")
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let name = path.file_stem()?.to_str()?.to_owned();
+                let is_case = path.extension()? == "expected" && path.with_extension("txt").exists();
+                is_case.then_some((name, path))
+            })
+            .collect();
+        cases.sort();
+
+        for (name, path) in cases {
+            let expected = fs::read_to_string(&path).expect("Can't read an .expected file");
+            for part in [1, 2] {
+                let header = format!("part{}:", part);
+                if expected.lines().any(|line| line.starts_with(&header)) {
+                    writeln!(
+                        tests,
+                        "#[test]\nfn day{:02}_{}_part{}() {{\n    check({}, {:?}, {});\n}}\n",
+                        day,
+                        identifier(&name),
+                        part,
+                        day,
+                        name,
+                        part
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+
+    fs::write(out_dir.join("example_tests.rs"), tests).expect("Can't write the generated tests");
+}
+
+// `name`, with anything that can't go in a function name replaced
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
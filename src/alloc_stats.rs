@@ -0,0 +1,169 @@
+use std::fmt;
+
+/// How much some code allocated: counted by the allocator that the `alloc-stats` feature installs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// How many times memory was allocated (or reallocated).
+    pub allocations: u64,
+    /// How many bytes were allocated in all.
+    pub bytes: u64,
+    /// The most memory that was allocated at once, in bytes, beyond what already was when the code
+    /// started.
+    pub peak: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} in all, {} at most",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+/// A number of bytes, in B, KiB, MiB or GiB as suits its size.
+pub fn format_bytes(bytes: u64) -> String {
+    let units = ["KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next in units {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    match unit {
+        "B" => format!("{}B", bytes),
+        _ => format!("{:.1}{}", size, unit),
+    }
+}
+
+/// Run `f`, and count what it allocates on this thread, if the `alloc-stats` feature is on.
+#[cfg(not(feature = "alloc-stats"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    (f(), None)
+}
+
+#[cfg(feature = "alloc-stats")]
+pub use counting::measure;
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    use super::AllocStats;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    // What this thread has allocated since it started.  Memory freed by a thread other than the one
+    // that allocated it throws the live count out, but solutions don't share memory between threads.
+    #[derive(Clone, Copy)]
+    struct Counts {
+        allocations: u64,
+        bytes: u64,
+        live: i64,
+        peak: i64,
+    }
+
+    thread_local! {
+        // Allocating here would recurse, so this must be const, with nothing to drop
+        static COUNTS: Cell<Counts> = const { Cell::new(Counts { allocations: 0, bytes: 0, live: 0, peak: 0 }) };
+    }
+
+    // Apply `change` to this thread's counts (unless the thread is being torn down)
+    fn update(change: impl FnOnce(&mut Counts)) {
+        let _ = COUNTS.try_with(|counts| {
+            let mut current = counts.get();
+            change(&mut current);
+            current.peak = current.peak.max(current.live);
+            counts.set(current);
+        });
+    }
+
+    /// The system allocator, counting what each thread allocates and frees.
+    struct Counting;
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            update(|counts| {
+                counts.allocations += 1;
+                counts.bytes += layout.size() as u64;
+                counts.live += layout.size() as i64;
+            });
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            update(|counts| {
+                counts.allocations += 1;
+                counts.bytes += layout.size() as u64;
+                counts.live += layout.size() as i64;
+            });
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            update(|counts| counts.live -= layout.size() as i64);
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            update(|counts| {
+                counts.allocations += 1;
+                counts.bytes += new_size.saturating_sub(layout.size()) as u64;
+                counts.live += new_size as i64 - layout.size() as i64;
+            });
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    /// Run `f`, and count what it allocates on this thread.  Measurements may be nested: the peak
+    /// of the outer one still counts whatever the inner one allocated.
+    pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+        let before = COUNTS.with(Cell::get);
+        // Start the peak afresh, from what's allocated now
+        update(|counts| counts.peak = counts.live);
+        let result = f();
+        let after = COUNTS.with(Cell::get);
+        update(|counts| counts.peak = counts.peak.max(before.peak));
+
+        let stats = AllocStats {
+            allocations: after.allocations - before.allocations,
+            bytes: after.bytes - before.bytes,
+            peak: (after.peak - before.live).max(0) as u64,
+        };
+        (result, Some(stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_format_bytes() {
+        assert_eq!(format_bytes(1023), "1023B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(5 << 30), "5.0GiB");
+    }
+
+    #[cfg(feature = "alloc-stats")]
+    #[test]
+    fn check_alloc_stats_counts() {
+        let (_, outer) = measure(|| {
+            let (kept, inner) = measure(|| vec![0u8; 4096]);
+            let inner = inner.unwrap();
+            assert_eq!((inner.allocations, inner.bytes, inner.peak), (1, 4096, 4096));
+            drop(kept);
+            let _small: Vec<u8> = Vec::with_capacity(100);
+        });
+        let outer = outer.unwrap();
+        assert_eq!((outer.allocations, outer.bytes, outer.peak), (2, 4196, 4096));
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+/// The answer to one part of a puzzle.
+///
+/// Numbers compare numerically, whichever variant holds them, and a picture compares equal to the
+/// letters it spells out (see [`Answer::ocr`]), so answers can be checked against ones recorded as
+/// text without worrying about how they're formatted.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    /// A number, as most answers are.
+    Int(i64),
+    /// A number too big for an `Int`.
+    BigInt(i128),
+    /// Anything else that's entered as text, like day 5's crate letters.
+    Text(String),
+    /// A picture drawn with `#` and `.`, one string per row, like day 10's CRT screen.  Use
+    /// [`Answer::grid`] to make one, so that stray whitespace is trimmed.
+    Grid(Vec<String>),
+    /// The part hasn't been solved yet, so there's no answer to give.
+    Unsolved,
+}
+
+impl Answer {
+    /// A picture answer, from its rows.  Trailing whitespace on each row, and blank rows at the top
+    /// and bottom, are dropped.
+    pub fn grid<S: AsRef<str>>(rows: impl IntoIterator<Item = S>) -> Answer {
+        let mut rows: Vec<String> = rows.into_iter().map(|row| row.as_ref().trim_end().to_owned()).collect();
+        while rows.last().is_some_and(String::is_empty) {
+            rows.pop();
+        }
+        let blank = rows.iter().take_while(|row| row.is_empty()).count();
+        Answer::Grid(rows.split_off(blank))
+    }
+
+    /// Read an answer written as text, as in the answers file: a whole number, a picture (if it
+    /// spans several lines), or otherwise just text.
+    pub fn parse(text: &str) -> Answer {
+        let text = text.trim();
+        if let Ok(n) = text.parse() {
+            Answer::Int(n)
+        } else if let Ok(n) = text.parse() {
+            Answer::BigInt(n)
+        } else if text.contains('\n') {
+            Answer::grid(text.lines())
+        } else {
+            Answer::Text(text.to_owned())
+        }
+    }
+
+    /// Whether the part has been solved.
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Answer::Unsolved)
+    }
+
+    /// The number, for either kind of number.
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Answer::Int(n) => Some(*n as i128),
+            Answer::BigInt(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The letters spelled out by a picture, in the font Advent of Code uses (each letter four
+    /// pixels wide and six high, with a column of space after it).  `None` if this isn't a picture,
+    /// or any of it isn't a letter in the font.
+    pub fn ocr(&self) -> Option<String> {
+        let Answer::Grid(rows) = self else {
+            return None;
+        };
+        if rows.len() != 6 {
+            return None;
+        }
+        let pixel = |row: &String, x: usize| row.as_bytes().get(x) == Some(&b'#');
+        let width = rows.iter().map(String::len).max().unwrap_or(0);
+
+        (0..width)
+            .step_by(5)
+            .map(|left| {
+                let glyph: Vec<String> = rows
+                    .iter()
+                    .map(|row| (left..left + 4).map(|x| if pixel(row, x) { '#' } else { '.' }).collect())
+                    .collect();
+                LETTERS
+                    .iter()
+                    .find(|(_, shape)| shape.iter().eq(glyph.iter()))
+                    .map(|(letter, _)| *letter)
+            })
+            .collect()
+    }
+
+    /// The answer as JSON: a number, a string (for a big number, so that nothing reading it loses
+    /// precision, or for text), an array of rows for a picture, or null if it's unsolved.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Answer::Int(n) => json!(n),
+            Answer::BigInt(n) => json!(n.to_string()),
+            Answer::Text(text) => json!(text),
+            Answer::Grid(rows) => json!(rows),
+            Answer::Unsolved => Value::Null,
+        }
+    }
+
+    /// Read back an answer written by `to_json`.
+    pub fn from_json(value: &Value) -> Option<Answer> {
+        match value {
+            Value::Null => Some(Answer::Unsolved),
+            Value::Number(n) => n.as_i64().map(Answer::Int),
+            Value::String(text) => Some(Answer::parse(text)),
+            Value::Array(rows) => rows
+                .iter()
+                .map(|row| row.as_str())
+                .collect::<Option<Vec<_>>>()
+                .map(Answer::grid),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Grid(a), Answer::Grid(b)) => a == b,
+            (Answer::Grid(_), Answer::Text(text)) => self.ocr().as_ref() == Some(text),
+            (Answer::Text(text), Answer::Grid(_)) => other.ocr().as_ref() == Some(text),
+            (Answer::Unsolved, Answer::Unsolved) => true,
+            _ => self.as_integer().is_some() && self.as_integer() == other.as_integer(),
+        }
+    }
+}
+
+/// The answer as it would be entered on the puzzle page: the letters in a picture, if they can be
+/// read, or else the picture itself.
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::BigInt(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => match self.ocr() {
+                Some(letters) => write!(f, "{}", letters),
+                None => write!(f, "{}", rows.join("\n")),
+            },
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_owned())
+    }
+}
+
+macro_rules! from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(n: $int) -> Self {
+                    match i64::try_from(n) {
+                        Ok(n) => Answer::Int(n),
+                        Err(_) => Answer::BigInt(i128::try_from(n).expect("answer too big even for a BigInt")),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+// The letters of the Advent of Code font
+const LETTERS: [(char, [&str; 6]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_answer_equality() {
+        assert_eq!(Answer::from(1832_usize), Answer::Int(1832));
+        assert_eq!(Answer::Int(21), Answer::BigInt(21));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInt(u64::MAX as i128));
+        assert_ne!(Answer::Int(21), Answer::Text("21".to_owned()));
+        assert_eq!(Answer::parse(" 13743542639657\n"), Answer::Int(13743542639657));
+        assert_eq!(Answer::parse("QNHWJVJZW"), Answer::from("QNHWJVJZW"));
+        assert_eq!(Answer::parse("\n#.\n.#\n"), Answer::grid(["#.  ", ".#"]));
+        assert_ne!(Answer::Unsolved, Answer::Int(0));
+    }
+
+    #[test]
+    fn check_answer_ocr() {
+        let picture = "\
+###...##..###..#..#.####.#..#.####...##.
+#..#.#..#.#..#.#.#..#....#.#..#.......#.
+#..#.#..#.#..#.##...###..##...###.....#.
+###..####.###..#.#..#....#.#..#.......#.
+#....#..#.#....#.#..#....#.#..#....#..#.
+#....#..#.#....#..#.#....#..#.####..##..";
+        let grid = Answer::parse(picture);
+        assert_eq!(grid.ocr().as_deref(), Some("PAPKFKEJ"));
+        assert_eq!(grid.to_string(), "PAPKFKEJ");
+        assert_eq!(grid, Answer::from("PAPKFKEJ"));
+        assert_ne!(grid, Answer::from("PAPKFKEL"));
+
+        let unreadable = Answer::grid(["#.", ".#"]);
+        assert_eq!(unreadable.ocr(), None);
+        assert_eq!(unreadable.to_string(), "#.\n.#");
+    }
+
+    #[test]
+    fn check_answer_json() {
+        let answers = [
+            Answer::Int(-3),
+            Answer::BigInt(1 << 70),
+            Answer::from("CMZ"),
+            Answer::grid(["#.", ".#"]),
+            Answer::Unsolved,
+        ];
+        for answer in answers {
+            assert_eq!(Answer::from_json(&answer.to_json()), Some(answer.clone()), "{:?}", answer);
+        }
+        assert_eq!(Answer::BigInt(1 << 70).to_json(), json!("1180591620717411303424"));
+    }
+}
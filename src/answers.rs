@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::helpers::{inputs_root, read_input_file};
+use crate::solution::{Answer, Params};
+
+/// Where the accepted answers for the real inputs are kept: `answers.json` in the inputs directory.
+pub fn answers_path() -> PathBuf {
+    inputs_root().join("answers.json")
+}
+
+/// The accepted answers for each day's real input.
+///
+/// The file is a JSON object keyed by day, each holding the answers for `part1` and/or `part2`:
+///
+/// ```json
+/// { "8": { "part1": "1832", "part2": "157320" } }
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    answers: BTreeMap<(usize, usize), Answer>,
+}
+
+/// How an answer compares with the accepted one.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer, actual: Result<Answer, String> },
+    /// There's no accepted answer to compare with.
+    Missing,
+}
+
+impl Answers {
+    /// Load the answers file.
+    pub fn load() -> Result<Answers, String> {
+        let path = answers_path();
+        let text = read_input_file("answers.json")
+            .map_err(|e| format!("can't read {} ({})", path.display(), e))?;
+        Answers::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Answers, String> {
+        let json: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let days = json.as_object().ok_or("expected an object keyed by day")?;
+
+        let mut answers = BTreeMap::new();
+        for (day, parts) in days {
+            let day_number = day
+                .parse()
+                .map_err(|_| format!("`{}` isn't a day number", day))?;
+            let parts = parts
+                .as_object()
+                .ok_or_else(|| format!("day {}: expected an object with part1 and/or part2", day))?;
+            for (part, answer) in parts {
+                let part_number = match part.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => return Err(format!("day {}: `{}` should be part1 or part2", day, part)),
+                };
+                let answer = answer
+                    .as_str()
+                    .ok_or_else(|| format!("day {} {}: answers must be strings", day, part))?;
+                answers.insert((day_number, part_number), Answer::parse(answer));
+            }
+        }
+        Ok(Answers { answers })
+    }
+
+    /// Every day with at least one accepted answer, in order.
+    pub fn days(&self) -> Vec<usize> {
+        let mut days: Vec<usize> = self.answers.keys().map(|(day, _)| *day).collect();
+        days.dedup();
+        days
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    /// Accept `answer` for `day` and `part`, unless there's an accepted answer already.
+    pub fn accept(&mut self, day: usize, part: usize, answer: Answer) {
+        self.answers.entry((day, part)).or_insert(answer);
+    }
+
+    /// Compare `actual` with the accepted answer for `day` and `part`.  (Numbers compare
+    /// numerically, and a picture matches the letters it spells; see `Answer`.)
+    pub fn check(&self, day: usize, part: usize, actual: &Result<Answer, String>) -> Verdict {
+        match (self.get(day, part), actual) {
+            (None, _) => Verdict::Missing,
+            (Some(expected), Ok(answer)) if expected == answer => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail {
+                expected: expected.clone(),
+                actual: actual.clone(),
+            },
+        }
+    }
+}
+
+/// What one of a day's named inputs should give, from the `.expected` file beside it.
+#[derive(Debug, Default, PartialEq)]
+pub struct Expected {
+    /// Parameters to parse the input with.
+    pub params: Params,
+    pub answers: Answers,
+}
+
+impl Expected {
+    /// Load the `.expected` file for `day`'s input called `name`, if there is one.
+    pub fn load(day: usize, name: &str) -> Result<Option<Expected>, String> {
+        let relative = format!("{:02}/{}.expected", day, name);
+        let path = inputs_root().join(&relative);
+        match read_input_file(&relative) {
+            Ok(text) => Expected::parse(day, &text)
+                .map(Some)
+                .map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("can't read {} ({})", path.display(), e)),
+        }
+    }
+
+    /// Parse an `.expected` file for one of `day`'s inputs.  The file starts with any parameters,
+    /// one `name=value` per line.  Each answer then follows `part1:` or `part2:`, on the same line,
+    /// or on the lines after if the answer is a picture:
+    ///
+    /// ```text
+    /// target_y=10
+    /// part1: 26
+    /// part2:
+    /// ##..##..
+    /// ###...##
+    /// ```
+    pub fn parse(day: usize, text: &str) -> Result<Expected, String> {
+        let mut expected = Expected::default();
+        let mut current: Option<(usize, Vec<&str>)> = None;
+
+        for (ix, line) in text.lines().enumerate() {
+            let header = match line.split_once(':') {
+                Some(("part1", rest)) => Some((1, rest.trim())),
+                Some(("part2", rest)) => Some((2, rest.trim())),
+                _ => None,
+            };
+            match (header, &mut current) {
+                (Some((part, rest)), _) => {
+                    if let Some((part, lines)) = current.take() {
+                        expected.answers.answers.insert((day, part), Answer::parse(&lines.join("\n")));
+                    }
+                    current = Some((part, if rest.is_empty() { vec![] } else { vec![rest] }));
+                }
+                (None, Some((_, lines))) => lines.push(line),
+                (None, None) if line.trim().is_empty() => {}
+                (None, None) => match line.split_once('=') {
+                    Some((name, value)) => {
+                        expected.params.insert(name.trim().to_owned(), value.trim().to_owned());
+                    }
+                    None => return Err(format!("line {}: expected name=value, part1: or part2:", ix + 1)),
+                },
+            }
+        }
+        if let Some((part, lines)) = current {
+            expected.answers.answers.insert((day, part), Answer::parse(&lines.join("\n")));
+        }
+        Ok(expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_answers_parse() {
+        let answers = Answers::parse(r##"{"8": {"part1": "21"}, "10": {"part2": "#.\n.#"}}"##).unwrap();
+        assert_eq!(answers.days(), vec![8, 10]);
+        assert_eq!(answers.get(8, 1), Some(&Answer::Int(21)));
+        assert_eq!(answers.get(8, 2), None);
+
+        assert!(Answers::parse(r#"{"8": {"part3": "21"}}"#).is_err());
+        assert!(Answers::parse(r#"{"8": {"part1": 21}}"#).is_err());
+        assert!(Answers::parse(r#"{"eight": {}}"#).is_err());
+    }
+
+    #[test]
+    fn check_answers_verdicts() {
+        let answers = Answers::parse(r##"{"10": {"part1": "13140", "part2": "#.\n.#"}}"##).unwrap();
+        assert_eq!(answers.check(10, 1, &Ok(Answer::Int(13140))), Verdict::Pass);
+        assert_eq!(answers.check(10, 1, &Ok(Answer::BigInt(13140))), Verdict::Pass);
+        assert_eq!(answers.check(10, 2, &Ok(Answer::grid(["", "#.", ".#  ", ""]))), Verdict::Pass);
+        assert_eq!(
+            answers.check(10, 1, &Ok(Answer::Int(13141))),
+            Verdict::Fail {
+                expected: Answer::Int(13140),
+                actual: Ok(Answer::Int(13141))
+            }
+        );
+        assert!(matches!(answers.check(10, 1, &Ok(Answer::from("13140"))), Verdict::Fail { .. }));
+        assert!(matches!(answers.check(10, 1, &Err("oops".to_owned())), Verdict::Fail { .. }));
+        assert_eq!(answers.check(11, 1, &Ok(Answer::Int(1))), Verdict::Missing);
+    }
+
+    #[test]
+    fn check_answers_expected() {
+        let expected = Expected::parse(10, "part1: 13140\npart2:\n#.\n.#\n").unwrap();
+        assert_eq!(expected.answers.get(10, 1), Some(&Answer::Int(13140)));
+        assert_eq!(expected.answers.get(10, 2), Some(&Answer::grid(["#.", ".#"])));
+        assert!(expected.params.is_empty());
+        assert!(Expected::parse(10, "13140\n").is_err());
+
+        let expected = Expected::load(9, "example2").unwrap().unwrap();
+        assert_eq!(expected.answers.get(9, 1), None);
+        assert_eq!(expected.answers.get(9, 2), Some(&Answer::Int(36)));
+        assert_eq!(Expected::load(9, "real"), Ok(None));
+    }
+
+    #[test]
+    fn check_answers_expected_params() {
+        let expected = Expected::load(15, "example1").unwrap().unwrap();
+        assert_eq!(expected.params.get("target_y").map(String::as_str), Some("10"));
+        assert_eq!(expected.answers.get(15, 1), Some(&Answer::Int(26)));
+    }
+
+    #[test]
+    fn check_answers_file() {
+        let answers = Answers::load().unwrap();
+        assert_eq!(answers.get(8, 1), Some(&Answer::Int(1832)));
+        assert_eq!(answers.get(9, 2), Some(&Answer::Int(2443)));
+    }
+}
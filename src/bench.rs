@@ -0,0 +1,231 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use aoc2022_rs_jtgs::error::SolveError;
+use aoc2022_rs_jtgs::helpers::format_duration;
+use aoc2022_rs_jtgs::solution::{Answer, Params, Solver};
+
+/// How long to keep sampling each day for, when not given a fixed number of iterations.
+pub const DEFAULT_BUDGET: Duration = Duration::from_secs(2);
+
+/// How many times to run the day, not counting warm-up runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    /// Exactly this many times.
+    Iterations(usize),
+    /// As many times as fit in this time budget (but at least once).
+    Budget(Duration),
+}
+
+/// Summary statistics for a set of timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    /// Summarise `samples`, which must not be empty.
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        // Nearest-rank percentile
+        let p95 = sorted[(n * 95).div_ceil(100).max(1) - 1];
+
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            p95,
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Timings for each step of one day.
+pub struct DayBench {
+    pub day: usize,
+    /// Which variant of the solution this is, if the day has more than one.
+    pub variant: Option<&'static str>,
+    /// (step name, stats), for the parse and each part that was run.
+    pub steps: Vec<(&'static str, Stats)>,
+}
+
+/// Run `solution` against `input` repeatedly, timing the parse and each selected part separately.
+pub fn bench_day(
+    solution: &dyn Solver,
+    input: &str,
+    params: &Params,
+    parts: [bool; 2],
+    warmup: usize,
+    repeat: Repeat,
+) -> Result<DayBench, SolveError> {
+    let mut samples: [Vec<Duration>; 3] = Default::default();
+    let mut started = Instant::now();
+
+    for iteration in 0.. {
+        // The budget is for timed runs, so it starts once the warm-up is over
+        if iteration == warmup {
+            started = Instant::now();
+        }
+        let done = match repeat {
+            Repeat::Iterations(n) => iteration >= warmup + n,
+            Repeat::Budget(budget) => iteration > warmup && started.elapsed() >= budget,
+        };
+        if done {
+            break;
+        }
+
+        let timings = time_once(solution, input, params, parts)?;
+        if iteration >= warmup {
+            for (step, timing) in timings.into_iter().enumerate() {
+                samples[step].extend(timing);
+            }
+        }
+    }
+
+    let names = ["parse", "part 1", "part 2"];
+    Ok(DayBench {
+        day: solution.day(),
+        variant: None,
+        steps: names
+            .into_iter()
+            .zip(samples.iter())
+            .filter(|(_, s)| !s.is_empty())
+            .map(|(name, s)| (name, Stats::from_samples(s)))
+            .collect(),
+    })
+}
+
+// Time the parse and (if selected) each part once
+fn time_once(
+    solution: &dyn Solver,
+    input: &str,
+    params: &Params,
+    parts: [bool; 2],
+) -> Result<[Option<Duration>; 3], SolveError> {
+    let start = Instant::now();
+    let parsed = solution.parse_any_with(black_box(input), params)?;
+    let mut timings = [Some(start.elapsed()), None, None];
+
+    for (ix, selected) in parts.into_iter().enumerate() {
+        if selected {
+            let start = Instant::now();
+            let answer = match ix {
+                0 => solution.part1_any(parsed.as_ref()),
+                _ => solution.part2_any(parsed.as_ref()),
+            };
+            let elapsed = start.elapsed();
+            // There's nothing worth timing in a part that hasn't been solved
+            if black_box(answer?) != Answer::Unsolved {
+                timings[ix + 1] = Some(elapsed);
+            }
+        }
+    }
+
+    Ok(timings)
+}
+
+/// Print a table of results, with a column for the variant if any day has more than one, so that
+/// they can be compared side by side.
+pub fn print_table(results: &[DayBench]) {
+    let width = results.iter().filter_map(|result| result.variant).map(str::len).max().unwrap_or(0);
+    let variant_column = |name: &str| match width {
+        0 => String::new(),
+        _ => format!("{:<width$}  ", name, width = width.max("Variant".len())),
+    };
+    println!(
+        "{:>3}  {}{:<6}  {:>6}  {:>11}  {:>11}  {:>11}  {:>11}  {:>11}",
+        "Day",
+        variant_column("Variant"),
+        "Step",
+        "Runs",
+        "Min",
+        "Median",
+        "Mean",
+        "p95",
+        "Std dev"
+    );
+    for result in results {
+        for (step, stats) in &result.steps {
+            println!(
+                "{:>3}  {}{:<6}  {:>6}  {:>11}  {:>11}  {:>11}  {:>11}  {:>11}",
+                result.day,
+                variant_column(result.variant.unwrap_or("")),
+                step,
+                stats.runs,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.mean),
+                format_duration(stats.p95),
+                format_duration(stats.std_dev),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    #[test]
+    fn check_stats_odd() {
+        let stats = Stats::from_samples(&ms(&[5, 1, 3, 2, 4]));
+        assert_eq!(stats.runs, 5);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean.as_micros(), 3000);
+        assert_eq!(stats.p95, Duration::from_millis(5));
+        // Sample standard deviation of 1..=5 is sqrt(2.5)
+        assert_eq!(stats.std_dev.as_micros(), 1581);
+    }
+
+    #[test]
+    fn check_stats_even_and_single() {
+        let stats = Stats::from_samples(&ms(&[4, 1, 3, 2]));
+        assert_eq!(stats.median.as_micros(), 2500);
+
+        let stats = Stats::from_samples(&ms(&[7]));
+        assert_eq!((stats.min, stats.median, stats.p95), (ms(&[7])[0], ms(&[7])[0], ms(&[7])[0]));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+    }
+
+    #[test]
+    fn check_stats_p95() {
+        let samples: Vec<u64> = (1..=100).collect();
+        assert_eq!(Stats::from_samples(&ms(&samples)).p95, Duration::from_millis(95));
+    }
+
+    #[test]
+    fn check_bench_day_counts_runs() {
+        let params = Params::new();
+        let result = bench_day(&aoc2022_rs_jtgs::day00::Day00, "1, 2", &params, [false, true], 2, Repeat::Iterations(3)).unwrap();
+        let steps: Vec<(&str, usize)> = result.steps.iter().map(|(s, stats)| (*s, stats.runs)).collect();
+        assert_eq!(steps, vec![("parse", 3), ("part 2", 3)]);
+    }
+}
@@ -0,0 +1,989 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::LevelFilter;
+
+use aoc2022_rs_jtgs::helpers::REAL_INPUT;
+
+use crate::bench::{Repeat, DEFAULT_BUDGET};
+use crate::history::DEFAULT_THRESHOLD;
+use crate::output::Format;
+
+pub const USAGE: &str = "\
+Usage: aoc2022-rs-jtgs [COMMAND] [OPTIONS]
+
+Commands:
+  run [DAYS]    Run the given days (default: every day from 1 to 25)
+  bench [DAYS]  Run the given days repeatedly and report timing statistics, and add them
+                to the history in bench-history.jsonl
+  perf-diff [DAYS]
+                Compare the latest timings in the history with an earlier commit's
+  verify [DAYS] Check the answers for the real inputs against inputs/answers.json
+                (default: every day in that file)
+  list          List the solutions the runner knows about, and each day's inputs
+  status        Show a grid of which parts of which days are done
+  report [DAYS] --html <PATH>
+                Run the given days on their real inputs, and write a page to PATH with
+                each answer, its time and whether it's right, and each day's bench history
+  new DAY       Start a new day: write src/dayNN.rs from templates/day.rs, and create
+                its input files.  Won't overwrite an existing module
+  watch DAY     Rebuild and re-run the day on each of its inputs whenever its module or
+                inputs change, comparing the answers with the expected ones
+  fetch DAYS    Download the real inputs for the given days, unless they're already there
+  submit DAY PART
+                Run one part on the real input and submit its answer
+  help          Show this message
+
+DAYS is a comma-separated list of days and ranges, e.g. `3-7,12`.  A bare
+DAYS argument (e.g. `aoc2022-rs-jtgs 8`) is short for `run DAYS`.
+
+Options for run and bench:
+  --part <1|2|both>    Which part(s) to run (default: both)
+  --named <NAME>       Use the input called NAME (inputs/<day>/<NAME>.txt) instead of
+                       the real input
+  --example <N>        Short for `--named exampleN`
+  --input <PATH>       Read the puzzle input from PATH, or from stdin if PATH is `-`
+  --release-check      Refuse to run unless this is a release build
+
+Options for run, bench and verify:
+  --variant <NAME>     Use the variant of each day's solution called NAME, for days solved
+                       more than one way (`list` shows them)
+  --all-variants       Use every variant of each day's solution.  For run and verify, it's
+                       a failure if they don't all give the same answers
+
+Options for run:
+  --jobs <N>           Run up to N days at once (default: 1)
+  --timeout <TIME>     Give up on a day after TIME, e.g. `10s`.  Each day then runs in a
+                       process of its own, which is killed if it takes too long
+  --format <FORMAT>    How to print results: text (the default), json, csv or jsonl
+                       (JSON Lines).  Every format but text has one record per part,
+                       with the day, part, status, answer, elapsed time and input hash.
+                       The status is ok, error, stub (unsolved), timeout or
+                       not-implemented (the day has no solution module)
+
+Options for bench:
+  --iterations <N>     Time exactly N runs of each day
+  --budget <TIME>      Keep running each day for TIME, e.g. `500ms` or `10s` (default: 2s)
+  --warmup <N>         Untimed runs before timing starts (default: 1)
+  --no-history         Don't add the timings to the history (debug builds never do)
+
+Options for perf-diff:
+  --baseline <COMMIT>  Compare with the timings at COMMIT (or the commit starting with
+                       COMMIT), rather than at the commit benched before the latest one
+  --threshold <PCT>    Call a step that got more than PCT percent slower a regression, and
+                       fail if there are any (default: 10)
+  --machine <NAME>     Use the timings made on NAME (default: this machine; $AOC_MACHINE
+                       names it, if its host name won't do)
+
+Options for verify:
+  --part <1|2|both>    Which part(s) to check (default: both)
+  --all-inputs         Also check every other input with a <NAME>.expected file beside it
+
+Options for report:
+  --html <PATH>        Where to write the page (needed)
+  --jobs <N>           Run up to N days at once (default: 1)
+  --timeout <TIME>     Give up on a day after TIME, e.g. `10s`
+
+Options for watch:
+  --timeout <TIME>     Give up on an input after TIME (default: 1m)
+
+Options for new, fetch and submit:
+  --year <YEAR>        The year of the puzzle (default: the year of the day's solution,
+                       or 2022)
+
+Options for every command:
+  -v, -vv, -vvv        Log what the solutions say they're doing, to stderr: -v for info,
+                       -vv for debug and -vvv for trace messages
+  -q                   Log nothing, not even warnings
+                       $AOC_LOG sets the levels too, for every day or one day at a time,
+                       e.g. `AOC_LOG=warn,day16=debug`
+
+fetch and submit talk to the Advent of Code site, logged in with the session cookie
+from $AOC_SESSION, or the `session` key of aoc.json in the repository.  Set $AOC_URL
+(or the `url` key) to use another server.
+";
+
+/// The year of the puzzles, unless `--year` says otherwise.
+pub const DEFAULT_YEAR: u16 = 2022;
+
+/// What the runner has been asked to do.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    Bench(BenchOptions),
+    PerfDiff(PerfDiffOptions),
+    Report(ReportOptions),
+    Verify(VerifyOptions),
+    New(NewOptions),
+    Watch(WatchOptions),
+    Fetch(FetchOptions),
+    Submit(SubmitOptions),
+    Status,
+    /// Run one day and print its result as JSON, for the runner to read back.  This is how
+    /// `--timeout` runs each day in a process of its own.
+    RunChild(RunOptions),
+    List,
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOptions {
+    pub days: Vec<usize>,
+    pub parts: Parts,
+    pub input: InputSource,
+    pub variant: Variants,
+    pub release_check: bool,
+    pub format: Format,
+    /// How many days to run at once.
+    pub jobs: usize,
+    /// How long each day may take, if it is limited.
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    pub run: RunOptions,
+    pub warmup: usize,
+    pub repeat: Repeat,
+    /// Whether to add the timings to the history.
+    pub record: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PerfDiffOptions {
+    /// The days to compare, or `None` for every day in the history.
+    pub days: Option<Vec<usize>>,
+    /// The commit to compare with, or `None` for the one benched before the latest.
+    pub baseline: Option<String>,
+    /// How much slower a step may get before it's a regression, in percent.
+    pub threshold: f64,
+    /// Whose timings to compare, or `None` for this machine's.
+    pub machine: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// The days to check, or `None` for every day with a recorded answer.
+    pub days: Option<Vec<usize>>,
+    pub parts: Parts,
+    pub variant: Variants,
+    /// Also check every other input with an `.expected` file.
+    pub all_inputs: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReportOptions {
+    pub days: Vec<usize>,
+    /// Where to write the HTML page.
+    pub html: PathBuf,
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NewOptions {
+    pub day: usize,
+    pub year: u16,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WatchOptions {
+    pub day: usize,
+    /// How long each input may take.
+    pub timeout: Duration,
+}
+
+/// How long `watch` lets each input take, unless `--timeout` says otherwise.
+pub const DEFAULT_WATCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FetchOptions {
+    pub days: Vec<usize>,
+    /// The year of the puzzles, if it was given.
+    pub year: Option<u16>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SubmitOptions {
+    pub day: usize,
+    pub part: usize,
+    /// The year of the puzzle, if it was given.
+    pub year: Option<u16>,
+}
+
+/// Which parts of each puzzle to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    One,
+    Two,
+    Both,
+}
+
+impl Parts {
+    pub fn includes(self, part: usize) -> bool {
+        matches!((self, part), (Parts::Both, _) | (Parts::One, 1) | (Parts::Two, 2))
+    }
+
+    fn arg(self) -> &'static str {
+        match self {
+            Parts::One => "1",
+            Parts::Two => "2",
+            Parts::Both => "both",
+        }
+    }
+}
+
+/// Which variants of each day's solution to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Variants {
+    /// The first one registered.
+    Default,
+    Named(String),
+    All,
+}
+
+impl Variants {
+    /// The name of the one variant to run, or `None` for the default (or for all of them).
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Variants::Named(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// Where to read the puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// One of the day's inputs from the inputs directory, e.g. `real` or `example1`.
+    Named(String),
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// The day's real input.
+    pub fn real() -> InputSource {
+        InputSource::Named(REAL_INPUT.to_owned())
+    }
+}
+
+/// A problem with the command line, to be shown alongside the usage.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CliError(String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn error<T>(message: impl Into<String>) -> Result<T, CliError> {
+    Err(CliError(message.into()))
+}
+
+/// The child process command line for running `day` with `options`.
+pub fn child_args(day: usize, options: &RunOptions) -> Vec<String> {
+    let mut args = vec![CHILD_COMMAND.to_owned(), day.to_string()];
+    args.extend(["--part".to_owned(), options.parts.arg().to_owned()]);
+    match &options.input {
+        InputSource::Named(name) => args.extend(["--named".to_owned(), name.clone()]),
+        InputSource::File(path) => args.extend(["--input".to_owned(), path.display().to_string()]),
+        InputSource::Stdin => args.extend(["--input".to_owned(), "-".to_owned()]),
+    }
+    if let Some(name) = options.variant.name() {
+        args.extend(["--variant".to_owned(), name.to_owned()]);
+    }
+    args
+}
+
+// Not in the usage, since it's only for the runner's own use
+const CHILD_COMMAND: &str = "__run-day";
+
+const COMMANDS: [&str; 15] = [
+    "list", "help", "--help", "-h", "run", "bench", "perf-diff", "verify", "report", "new", "watch", "fetch", "submit",
+    "status", CHILD_COMMAND,
+];
+
+/// Take the options saying how much to log (`-v`, `-vv`, `-vvv` and `-q`, which may go anywhere)
+/// out of `args`, and return the level they ask for, if any.
+pub fn take_log_level(args: &mut Vec<String>) -> Result<Option<LevelFilter>, CliError> {
+    let (mut verbose, mut quiet) = (0, false);
+    args.retain(|arg| match arg.as_str() {
+        "-q" | "--quiet" => {
+            quiet = true;
+            false
+        }
+        "--verbose" => {
+            verbose += 1;
+            false
+        }
+        _ if arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == 'v') => {
+            verbose += arg.len() - 1;
+            false
+        }
+        _ => true,
+    });
+
+    match (verbose, quiet) {
+        (0, false) => Ok(None),
+        (0, true) => Ok(Some(LevelFilter::Off)),
+        (_, true) => error("-v and -q can't both be given"),
+        (1, _) => Ok(Some(LevelFilter::Info)),
+        (2, _) => Ok(Some(LevelFilter::Debug)),
+        _ => Ok(Some(LevelFilter::Trace)),
+    }
+}
+
+/// Parse the command line (excluding the program name).
+pub fn parse_args(args: &[String]) -> Result<Command, CliError> {
+    let mut args = args.iter().map(String::as_str).peekable();
+
+    let command = match args.peek() {
+        None => "run",
+        Some(arg) if COMMANDS.contains(arg) => args.next().unwrap(),
+        // A leading day list means "run"
+        Some(arg) if arg.starts_with(|c: char| c.is_ascii_digit()) => "run",
+        Some(arg) if arg.starts_with('-') => "run",
+        Some(arg) => return error(format!("unknown command `{}`", arg)),
+    };
+
+    match command {
+        "list" | "status" => {
+            if let Some(arg) = args.next() {
+                return error(format!("unexpected argument `{}` to {}", arg, command));
+            }
+            Ok(if command == "list" { Command::List } else { Command::Status })
+        }
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        "bench" => parse_run(args, true).map(Command::Bench),
+        "perf-diff" => parse_perf_diff(args).map(Command::PerfDiff),
+        "verify" => parse_verify(args).map(Command::Verify),
+        "report" => parse_report(args).map(Command::Report),
+        "new" => parse_new(args).map(Command::New),
+        "watch" => parse_watch(args).map(Command::Watch),
+        "fetch" => parse_fetch(args).map(Command::Fetch),
+        "submit" => parse_submit(args).map(Command::Submit),
+        CHILD_COMMAND => parse_run(args, false).map(|bench| Command::RunChild(bench.run)),
+        _ => parse_run(args, false).map(|bench| Command::Run(bench.run)),
+    }
+}
+
+// Parse the options for `run`, or (if `bench` is set) for `bench`
+fn parse_run<'a>(mut args: impl Iterator<Item = &'a str>, bench: bool) -> Result<BenchOptions, CliError> {
+    let mut days = None;
+    let mut parts = Parts::Both;
+    let mut input = None;
+    let mut variant = None;
+    let mut release_check = false;
+    let mut format = Format::Text;
+    let mut jobs = 1;
+    let mut timeout = None;
+    let mut warmup = 1;
+    let mut record = true;
+    let mut repeat = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| match args.next() {
+            Some(value) => Ok(value),
+            None => error(format!("{} needs a value", name)),
+        };
+
+        match arg {
+            "--part" => parts = parse_parts(value(arg)?)?,
+            "--input" | "--named" | "--example" if input.is_some() => {
+                return error("only one of --input, --named and --example may be given");
+            }
+            "--input" => {
+                input = Some(match value(arg)? {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::File(PathBuf::from(path)),
+                })
+            }
+            "--named" => input = Some(InputSource::Named(value(arg)?.to_owned())),
+            "--example" => {
+                let n = value(arg)?;
+                input = match n.parse::<usize>() {
+                    Ok(n) if n > 0 => Some(InputSource::Named(format!("example{}", n))),
+                    _ => return error(format!("--example must be a positive number, not `{}`", n)),
+                }
+            }
+            "--variant" | "--all-variants" if variant.is_some() => {
+                return error("only one of --variant and --all-variants may be given");
+            }
+            "--variant" => variant = Some(Variants::Named(value(arg)?.to_owned())),
+            "--all-variants" => variant = Some(Variants::All),
+            "--release-check" => release_check = true,
+            "--format" if bench => return error("--format only applies to run"),
+            "--format" => {
+                let name = value(arg)?;
+                format = Format::from_name(name)
+                    .ok_or_else(|| CliError(format!("--format must be text, json, csv or jsonl, not `{}`", name)))?;
+            }
+            "--jobs" if bench => return error("--jobs only applies to run, since parallel runs spoil the timings"),
+            "--jobs" => {
+                let n = value(arg)?;
+                jobs = match n.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return error(format!("--jobs must be a positive number, not `{}`", n)),
+                }
+            }
+            "--timeout" if bench => return error("--timeout only applies to run"),
+            "--timeout" => timeout = Some(parse_duration(value(arg)?)?),
+            "--iterations" | "--budget" | "--warmup" | "--no-history" if !bench => {
+                return error(format!("{} only applies to bench", arg));
+            }
+            "--iterations" | "--budget" if repeat.is_some() => {
+                return error("only one of --iterations and --budget may be given");
+            }
+            "--iterations" => {
+                let n = value(arg)?;
+                repeat = match n.parse() {
+                    Ok(n) if n > 0 => Some(Repeat::Iterations(n)),
+                    _ => return error(format!("--iterations must be a positive number, not `{}`", n)),
+                }
+            }
+            "--budget" => repeat = Some(Repeat::Budget(parse_duration(value(arg)?)?)),
+            "--warmup" => {
+                let n = value(arg)?;
+                warmup = n
+                    .parse()
+                    .or_else(|_| error(format!("--warmup must be a number, not `{}`", n)))?;
+            }
+            "--no-history" => record = false,
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}`", arg)),
+            _ if days.is_some() => return error(format!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(arg)?),
+        }
+    }
+
+    let days = days.unwrap_or_else(|| (1..=25).collect());
+    let input = input.unwrap_or_else(InputSource::real);
+    if matches!(input, InputSource::File(_) | InputSource::Stdin) && days.len() != 1 {
+        return error("--input needs exactly one day");
+    }
+
+    Ok(BenchOptions {
+        run: RunOptions {
+            days,
+            parts,
+            input,
+            variant: variant.unwrap_or(Variants::Default),
+            release_check,
+            format,
+            jobs,
+            timeout,
+        },
+        warmup,
+        repeat: repeat.unwrap_or(Repeat::Budget(DEFAULT_BUDGET)),
+        record,
+    })
+}
+
+// Parse the options for `perf-diff`
+fn parse_perf_diff<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<PerfDiffOptions, CliError> {
+    let mut days = None;
+    let mut baseline = None;
+    let mut threshold = DEFAULT_THRESHOLD;
+    let mut machine = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| CliError(format!("{} needs a value", arg)));
+        match arg {
+            "--baseline" => baseline = Some(value()?.to_owned()),
+            "--machine" => machine = Some(value()?.to_owned()),
+            "--threshold" => {
+                let pct = value()?;
+                threshold = match pct.trim_end_matches('%').parse::<f64>() {
+                    Ok(pct) if pct >= 0.0 => pct,
+                    _ => return error(format!("--threshold must be a percentage, like `10`, not `{}`", pct)),
+                }
+            }
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}` to perf-diff", arg)),
+            _ if days.is_some() => return error(format!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(arg)?),
+        }
+    }
+
+    Ok(PerfDiffOptions {
+        days,
+        baseline,
+        threshold,
+        machine,
+    })
+}
+
+// Parse the options for `verify`
+fn parse_verify<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<VerifyOptions, CliError> {
+    let mut days = None;
+    let mut parts = Parts::Both;
+    let mut variant = None;
+    let mut all_inputs = false;
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "--part" => match args.next() {
+                Some(value) => parts = parse_parts(value)?,
+                None => return error("--part needs a value"),
+            },
+            "--variant" | "--all-variants" if variant.is_some() => {
+                return error("only one of --variant and --all-variants may be given");
+            }
+            "--variant" => match args.next() {
+                Some(value) => variant = Some(Variants::Named(value.to_owned())),
+                None => return error("--variant needs a value"),
+            },
+            "--all-variants" => variant = Some(Variants::All),
+            "--all-inputs" => all_inputs = true,
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}` to verify", arg)),
+            _ if days.is_some() => return error(format!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(arg)?),
+        }
+    }
+
+    Ok(VerifyOptions {
+        days,
+        parts,
+        variant: variant.unwrap_or(Variants::Default),
+        all_inputs,
+    })
+}
+
+// Parse the options for `report`
+fn parse_report<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<ReportOptions, CliError> {
+    let mut days = None;
+    let mut html = None;
+    let mut jobs = 1;
+    let mut timeout = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| CliError(format!("{} needs a value", arg)));
+        match arg {
+            "--html" => html = Some(PathBuf::from(value()?)),
+            "--jobs" => {
+                let n = value()?;
+                jobs = match n.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return error(format!("--jobs must be a positive number, not `{}`", n)),
+                }
+            }
+            "--timeout" => timeout = Some(parse_duration(value()?)?),
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}` to report", arg)),
+            _ if days.is_some() => return error(format!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(arg)?),
+        }
+    }
+
+    match html {
+        Some(html) => Ok(ReportOptions {
+            days: days.unwrap_or_else(|| (1..=25).collect()),
+            html,
+            jobs,
+            timeout,
+        }),
+        None => error("report needs --html <PATH> to say where to write the page"),
+    }
+}
+
+// Parse the options for `new`
+fn parse_new<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<NewOptions, CliError> {
+    let mut day = None;
+    let mut year = DEFAULT_YEAR;
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "--year" => year = parse_year(args.next())?,
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}` to new", arg)),
+            _ if day.is_some() => return error(format!("unexpected argument `{}`", arg)),
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+
+    match day {
+        Some(day) => Ok(NewOptions { day, year }),
+        None => error("new needs a day"),
+    }
+}
+
+// Parse the options for `watch`
+fn parse_watch<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<WatchOptions, CliError> {
+    let mut day = None;
+    let mut timeout = DEFAULT_WATCH_TIMEOUT;
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "--timeout" => match args.next() {
+                Some(value) => timeout = parse_duration(value)?,
+                None => return error("--timeout needs a value"),
+            },
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}` to watch", arg)),
+            _ if day.is_some() => return error(format!("unexpected argument `{}`", arg)),
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+
+    match day {
+        Some(day) => Ok(WatchOptions { day, timeout }),
+        None => error("watch needs a day"),
+    }
+}
+
+// Parse the options for `fetch`
+fn parse_fetch<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<FetchOptions, CliError> {
+    let mut days = None;
+    let mut year = None;
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "--year" => year = Some(parse_year(args.next())?),
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}` to fetch", arg)),
+            _ if days.is_some() => return error(format!("unexpected argument `{}`", arg)),
+            _ => days = Some(parse_days(arg)?),
+        }
+    }
+
+    match days {
+        Some(days) => Ok(FetchOptions { days, year }),
+        None => error("fetch needs the days to download"),
+    }
+}
+
+// Parse the options for `submit`
+fn parse_submit<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<SubmitOptions, CliError> {
+    let mut day = None;
+    let mut part = None;
+    let mut year = None;
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "--year" => year = Some(parse_year(args.next())?),
+            _ if arg.starts_with('-') => return error(format!("unknown option `{}` to submit", arg)),
+            _ if day.is_none() => day = Some(parse_day(arg)?),
+            _ if part.is_none() => {
+                part = match arg {
+                    "1" => Some(1),
+                    "2" => Some(2),
+                    _ => return error(format!("the part must be 1 or 2, not `{}`", arg)),
+                }
+            }
+            _ => return error(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    match (day, part) {
+        (Some(day), Some(part)) => Ok(SubmitOptions { day, part, year }),
+        _ => error("submit needs a day and a part"),
+    }
+}
+
+fn parse_year(value: Option<&str>) -> Result<u16, CliError> {
+    let value = value.ok_or(CliError("--year needs a value".to_owned()))?;
+    match value.parse() {
+        Ok(year) if year >= 2015 => Ok(year),
+        _ => error(format!("`{}` isn't an Advent of Code year", value)),
+    }
+}
+
+fn parse_parts(value: &str) -> Result<Parts, CliError> {
+    match value {
+        "1" => Ok(Parts::One),
+        "2" => Ok(Parts::Two),
+        "both" => Ok(Parts::Both),
+        other => error(format!("--part must be 1, 2 or both, not `{}`", other)),
+    }
+}
+
+/// Parse a duration such as `500ms`, `10s` or `2m`.
+pub fn parse_duration(value: &str) -> Result<Duration, CliError> {
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let not_a_duration = || CliError(format!("`{}` isn't a duration; try e.g. 500ms, 10s or 2m", value));
+    let seconds = match (number.parse::<f64>(), unit) {
+        (Ok(n), "ms") => n / 1000.0,
+        (Ok(n), "s") => n,
+        (Ok(n), "m") => n * 60.0,
+        _ => return Err(not_a_duration()),
+    };
+    // Too many digits make a number too big for a Duration, or even infinity
+    Duration::try_from_secs_f64(seconds).map_err(|_| not_a_duration())
+}
+
+/// Parse a list of days such as `3-7,12`, returning the days in order without duplicates.
+pub fn parse_days(spec: &str) -> Result<Vec<usize>, CliError> {
+    let mut days = Vec::new();
+
+    for item in spec.split(',') {
+        let (first, last) = match item.split_once('-') {
+            Some((first, last)) => (parse_day(first)?, parse_day(last)?),
+            None => (parse_day(item)?, parse_day(item)?),
+        };
+        if first > last {
+            return error(format!("`{}` is backwards; did you mean {}-{}?", item, last, first));
+        }
+        days.extend(first..=last);
+    }
+
+    days.sort_unstable();
+    days.dedup();
+    Ok(days)
+}
+
+fn parse_day(day: &str) -> Result<usize, CliError> {
+    match day.trim().parse() {
+        Ok(day) if day <= 25 => Ok(day),
+        Ok(day) => error(format!("there is no day {}; days run from 0 to 25", day)),
+        Err(_) => error(format!("`{}` isn't a day number", day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, CliError> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    fn run(days: Vec<usize>, parts: Parts, input: InputSource) -> Command {
+        Command::Run(RunOptions {
+            days,
+            parts,
+            input,
+            variant: Variants::Default,
+            release_check: false,
+            format: Format::Text,
+            jobs: 1,
+            timeout: None,
+        })
+    }
+
+    #[test]
+    fn check_cli_defaults() {
+        assert_eq!(parse(""), Ok(run((1..=25).collect(), Parts::Both, InputSource::real())));
+        assert_eq!(parse("8"), Ok(run(vec![8], Parts::Both, InputSource::real())));
+        assert_eq!(parse("list"), Ok(Command::List));
+        assert_eq!(parse("status"), Ok(Command::Status));
+        assert!(parse("status 8").is_err());
+    }
+
+    #[test]
+    fn check_cli_day_ranges() {
+        assert_eq!(parse_days("3-7,12"), Ok(vec![3, 4, 5, 6, 7, 12]));
+        assert_eq!(parse_days("12,3,3-4"), Ok(vec![3, 4, 12]));
+        assert!(parse_days("7-3").unwrap_err().to_string().contains("did you mean 3-7"));
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn check_cli_run_options() {
+        assert_eq!(
+            parse("run 16 --part 2 --input -"),
+            Ok(run(vec![16], Parts::Two, InputSource::Stdin))
+        );
+        assert_eq!(
+            parse("run --example 2 9"),
+            Ok(run(vec![9], Parts::Both, InputSource::Named("example2".to_owned())))
+        );
+        assert_eq!(
+            parse("run 1-3 --named alice"),
+            Ok(run(vec![1, 2, 3], Parts::Both, InputSource::Named("alice".to_owned())))
+        );
+        assert!(parse("run --release-check 1-3").is_ok());
+        match parse("run 2 --variant table") {
+            Ok(Command::Run(options)) => {
+                assert_eq!(options.variant, Variants::Named("table".to_owned()));
+                assert_eq!(child_args(2, &options)[4..], ["--named", "real", "--variant", "table"]);
+            }
+            other => panic!("{:?}", other),
+        }
+        match parse("run 1-3 --format jsonl --jobs 4") {
+            Ok(Command::Run(options)) => assert_eq!((options.format, options.jobs), (Format::JsonLines, 4)),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_cli_errors() {
+        assert!(parse("run --part 3").is_err());
+        assert!(parse("run --part").is_err());
+        assert!(parse("run --frobnicate").is_err());
+        assert!(parse("run 1-3 --input x").is_err());
+        assert!(parse("run 1 --example 1 --input x").is_err());
+        assert!(parse("run 1 --example 1 --named alice").is_err());
+        assert!(parse("run 1 2").is_err());
+        assert!(parse("frobnicate").is_err());
+        assert!(parse("run 1 --iterations 3").is_err());
+        assert!(parse("run 1 --format xml").is_err());
+        assert!(parse("bench 1 --format json").is_err());
+        assert!(parse("run --jobs 0").is_err());
+        assert!(parse("bench --jobs 2").is_err());
+        assert!(parse("run 2 --variant table --all-variants").is_err());
+        assert!(parse("verify 2 --variant").is_err());
+    }
+
+    #[test]
+    fn check_cli_bench() {
+        let bench = |args| match parse(args) {
+            Ok(Command::Bench(options)) => (options.run.days, options.warmup, options.repeat),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(bench("bench 15-17"), (vec![15, 16, 17], 1, Repeat::Budget(DEFAULT_BUDGET)));
+        assert_eq!(bench("bench 8 --iterations 50 --warmup 0"), (vec![8], 0, Repeat::Iterations(50)));
+        assert_eq!(bench("bench 8 --budget 1.5s").2, Repeat::Budget(Duration::from_millis(1500)));
+        assert!(parse("bench 8 --budget 3 days").is_err());
+        assert!(parse("bench 8 --budget 1s --iterations 5").is_err());
+        assert!(matches!(parse("bench 8 --no-history"), Ok(Command::Bench(options)) if !options.record));
+        assert!(parse("run 8 --no-history").is_err());
+    }
+
+    #[test]
+    fn check_cli_report() {
+        assert_eq!(
+            parse("report 1-3 --html out.html --timeout 30s"),
+            Ok(Command::Report(ReportOptions {
+                days: vec![1, 2, 3],
+                html: PathBuf::from("out.html"),
+                jobs: 1,
+                timeout: Some(Duration::from_secs(30)),
+            }))
+        );
+        let all_days = parse("report --html out.html --jobs 4");
+        assert!(matches!(all_days, Ok(Command::Report(options)) if options.days.len() == 25));
+        assert!(parse("report 1-3").is_err());
+        assert!(parse("report --html").is_err());
+    }
+
+    #[test]
+    fn check_cli_perf_diff() {
+        assert_eq!(
+            parse("perf-diff"),
+            Ok(Command::PerfDiff(PerfDiffOptions {
+                days: None,
+                baseline: None,
+                threshold: DEFAULT_THRESHOLD,
+                machine: None,
+            }))
+        );
+        assert_eq!(
+            parse("perf-diff 8-10 --baseline 1a2b3c --threshold 5% --machine laptop"),
+            Ok(Command::PerfDiff(PerfDiffOptions {
+                days: Some(vec![8, 9, 10]),
+                baseline: Some("1a2b3c".to_owned()),
+                threshold: 5.0,
+                machine: Some("laptop".to_owned()),
+            }))
+        );
+        assert!(parse("perf-diff --threshold fast").is_err());
+        assert!(parse("perf-diff --baseline").is_err());
+    }
+
+    #[test]
+    fn check_cli_verify() {
+        assert_eq!(
+            parse("verify"),
+            Ok(Command::Verify(VerifyOptions {
+                days: None,
+                parts: Parts::Both,
+                variant: Variants::Default,
+                all_inputs: false,
+            }))
+        );
+        assert_eq!(
+            parse("verify 1-3 --part 2 --all-inputs --all-variants"),
+            Ok(Command::Verify(VerifyOptions {
+                days: Some(vec![1, 2, 3]),
+                parts: Parts::Two,
+                variant: Variants::All,
+                all_inputs: true,
+            }))
+        );
+        assert!(parse("verify --example 1").is_err());
+    }
+
+    #[test]
+    fn check_cli_new() {
+        assert_eq!(parse("new 18"), Ok(Command::New(NewOptions { day: 18, year: 2022 })));
+        assert_eq!(parse("new --year 2023 1"), Ok(Command::New(NewOptions { day: 1, year: 2023 })));
+        assert!(parse("new").is_err());
+        assert!(parse("new 1-3").is_err());
+        assert!(parse("new 26").is_err());
+        assert!(parse("new 1 --year 1999").is_err());
+    }
+
+    #[test]
+    fn check_cli_watch() {
+        assert_eq!(
+            parse("watch 16"),
+            Ok(Command::Watch(WatchOptions {
+                day: 16,
+                timeout: DEFAULT_WATCH_TIMEOUT,
+            }))
+        );
+        assert_eq!(
+            parse("watch 16 --timeout 5m"),
+            Ok(Command::Watch(WatchOptions {
+                day: 16,
+                timeout: Duration::from_secs(300),
+            }))
+        );
+        assert!(parse("watch").is_err());
+        assert!(parse("watch 1-3").is_err());
+    }
+
+    #[test]
+    fn check_cli_fetch_and_submit() {
+        assert_eq!(parse("fetch 1-3"), Ok(Command::Fetch(FetchOptions { days: vec![1, 2, 3], year: None })));
+        assert_eq!(
+            parse("submit 8 2 --year 2021"),
+            Ok(Command::Submit(SubmitOptions {
+                day: 8,
+                part: 2,
+                year: Some(2021),
+            }))
+        );
+        assert!(parse("fetch").is_err());
+        assert!(parse("submit 8").is_err());
+        assert!(parse("submit 8 3").is_err());
+        assert!(parse("submit 8 1 1").is_err());
+    }
+
+    #[test]
+    fn check_cli_log_level() {
+        let take = |args: &str| {
+            let mut args: Vec<String> = args.split_whitespace().map(String::from).collect();
+            take_log_level(&mut args).map(|level| (level, args.join(" ")))
+        };
+        assert_eq!(take("run 16"), Ok((None, "run 16".to_owned())));
+        assert_eq!(take("-v run 16"), Ok((Some(LevelFilter::Info), "run 16".to_owned())));
+        assert_eq!(take("run -vv 16 -v"), Ok((Some(LevelFilter::Trace), "run 16".to_owned())));
+        assert_eq!(take("verify -q"), Ok((Some(LevelFilter::Off), "verify".to_owned())));
+        assert!(take("run -v -q").is_err());
+    }
+
+    #[test]
+    fn check_cli_timeout() {
+        match parse("run 16 --timeout 10s --part 2 --example 1") {
+            Ok(Command::Run(options)) => {
+                assert_eq!(options.timeout, Some(Duration::from_secs(10)));
+                let args = child_args(16, &options);
+                assert_eq!(args, ["__run-day", "16", "--part", "2", "--named", "example1"]);
+                assert!(matches!(parse_args(&args), Ok(Command::RunChild(child)) if child.input == options.input));
+            }
+            other => panic!("{:?}", other),
+        }
+        assert!(parse("bench 16 --timeout 10s").is_err());
+    }
+
+    #[test]
+    fn check_cli_durations() {
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("10s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_duration("10").is_err());
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration(&format!("{}s", "9".repeat(400))).unwrap_err().0.contains("isn't a duration"));
+    }
+}
@@ -0,0 +1,338 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use aoc2022_rs_jtgs::solution::Answer;
+
+use crate::ledger::Verdict;
+
+/// The environment variable holding the session cookie to log in with.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// The environment variable holding the address of the server to talk to.
+pub const URL_VAR: &str = "AOC_URL";
+
+/// The config file, relative to the repository root: a JSON object with an optional `session` (the
+/// value of the `session` cookie from a logged-in browser) and `url`.  It's in .gitignore, since
+/// the session is as good as a password.
+pub const CONFIG_PATH: &str = "aoc.json";
+
+/// The real Advent of Code site.
+pub const DEFAULT_URL: &str = "https://adventofcode.com";
+
+// The site asks that automated requests say where they come from
+const USER_AGENT: &str = concat!("github.com/jtgs/aoc2022-rs ", env!("CARGO_PKG_VERSION"));
+
+// How long to wait for the server before giving up
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where to find the server, and how to log in to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The server's address, e.g. `https://adventofcode.com` (with no trailing `/`).
+    pub url: String,
+    pub session: Option<String>,
+}
+
+impl Config {
+    /// Read the config file in the repository at `root`, if there is one, then let `$AOC_SESSION`
+    /// and `$AOC_URL` override it.
+    pub fn load(root: &Path) -> Result<Config, String> {
+        let path = root.join(CONFIG_PATH);
+        let mut config = match fs::read_to_string(&path) {
+            Ok(text) => Config::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
+            Err(e) => return Err(format!("can't read {} ({})", path.display(), e)),
+        };
+
+        let var = |name| env::var(name).ok().filter(|value: &String| !value.is_empty());
+        if let Some(session) = var(SESSION_VAR) {
+            config.session = Some(session);
+        }
+        if let Some(url) = var(URL_VAR) {
+            config.url = url.trim_end_matches('/').to_owned();
+        }
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let json: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let field = |name| match &json[name] {
+            Value::Null => Ok(None),
+            Value::String(value) => Ok(Some(value.trim().to_owned())),
+            _ => Err(format!("`{}` should be a string", name)),
+        };
+
+        let mut config = Config::default();
+        if let Some(url) = field("url")? {
+            config.url = url.trim_end_matches('/').to_owned();
+        }
+        config.session = field("session")?;
+        Ok(config)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            url: DEFAULT_URL.to_owned(),
+            session: None,
+        }
+    }
+}
+
+/// What became of a request for a day's input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded,
+    /// The input was already there, so the server wasn't asked for it again.
+    Cached,
+}
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, with no hint as to which way.
+    Wrong,
+    /// An answer was submitted too recently; the message says how long to wait.
+    TooSoon(String),
+    /// The part has already been solved (or isn't open yet).
+    AlreadySolved,
+    /// A reply that couldn't be made sense of, as text.
+    Unrecognised(String),
+}
+
+impl Outcome {
+    /// What the server made of the answer, if it judged it at all.
+    pub fn verdict(&self) -> Option<Verdict> {
+        match self {
+            Outcome::Correct => Some(Verdict::Correct),
+            Outcome::TooHigh => Some(Verdict::TooHigh),
+            Outcome::TooLow => Some(Verdict::TooLow),
+            Outcome::Wrong => Some(Verdict::Wrong),
+            _ => None,
+        }
+    }
+}
+
+/// Talks to an Advent of Code server, logged in as the user whose session it has.
+pub struct Client {
+    url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Client, String> {
+        let session = config.session.clone().ok_or_else(|| {
+            format!(
+                "there's no session to log in with; set {} or the `session` key in {}",
+                SESSION_VAR, CONFIG_PATH
+            )
+        })?;
+        let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).user_agent(USER_AGENT).build();
+        Ok(Client {
+            url: config.url.clone(),
+            session,
+            agent,
+        })
+    }
+
+    /// Download the input for `day` of `year` to `path`, unless there's already an input there.
+    /// (An empty file, as `new` leaves, doesn't count.)  Inputs never change, so there's no need
+    /// to ask the server twice.
+    pub fn fetch_input(&self, year: u16, day: usize, path: &Path) -> Result<Fetched, String> {
+        if fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0) {
+            return Ok(Fetched::Cached);
+        }
+
+        let url = format!("{}/{}/day/{}/input", self.url, year, day);
+        let response = self.agent.get(&url).set("Cookie", &self.cookie()).call();
+        let input = read_response(&url, response)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("can't create {} ({})", dir.display(), e))?;
+        }
+        fs::write(path, input).map_err(|e| format!("can't write {} ({})", path.display(), e))?;
+        Ok(Fetched::Downloaded)
+    }
+
+    /// Submit `answer` as the answer to `part` of `day` of `year`.
+    pub fn submit(&self, year: u16, day: usize, part: usize, answer: &Answer) -> Result<Outcome, String> {
+        let url = format!("{}/{}/day/{}/answer", self.url, year, day);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", &part.to_string()), ("answer", &answer.to_string())]);
+        Ok(parse_outcome(&read_response(&url, response)?))
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+}
+
+// The body of a successful response, or what went wrong
+fn read_response(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, String> {
+    match response {
+        Ok(response) => response.into_string().map_err(|e| format!("can't read the reply from {} ({})", url, e)),
+        Err(ureq::Error::Status(code, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            Err(format!("{} said {}: {}", url, code, body.trim()))
+        }
+        Err(e) => Err(format!("can't reach the server ({})", e)),
+    }
+}
+
+/// Make sense of the page the server sends back after an answer is submitted.  The verdict is in
+/// the page's `<article>`, in prose.
+pub fn parse_outcome(page: &str) -> Outcome {
+    let article = match (page.find("<article"), page.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &page[start..end],
+        _ => page,
+    };
+    let text = strip_tags(article);
+
+    if text.contains("That's the right answer") {
+        Outcome::Correct
+    } else if text.contains("too high") {
+        Outcome::TooHigh
+    } else if text.contains("too low") {
+        Outcome::TooLow
+    } else if text.contains("That's not the right answer") {
+        Outcome::Wrong
+    } else if text.contains("answer too recently") {
+        Outcome::TooSoon(text)
+    } else if text.contains("the right level") {
+        Outcome::AlreadySolved
+    } else {
+        Outcome::Unrecognised(text)
+    }
+}
+
+// The text of some HTML, with its tags removed and its whitespace tidied up
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    // A server on a free local port that answers every request with `body`, and records each
+    // request's first line, cookie and body
+    fn mock_server(body: &'static str) -> (Config, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = Config {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            session: Some("53cr3t".to_owned()),
+        };
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let lower = line.to_ascii_lowercase();
+                    if let Some(value) = lower.strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    if request.is_empty() || lower.starts_with("cookie:") {
+                        request.push_str(line);
+                        request.push('\n');
+                    }
+                }
+                let mut content = vec![0; length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                seen.lock().unwrap().push(request);
+
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+                    .unwrap();
+            }
+        });
+        (config, requests)
+    }
+
+    #[test]
+    fn check_client_fetch_caches() {
+        let (config, requests) = mock_server("1000\n2000\n");
+        let client = Client::new(&config).unwrap();
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let path = dir.join("01/real.txt");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(client.fetch_input(2022, 1, &path), Ok(Fetched::Downloaded));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000\n");
+        assert_eq!(client.fetch_input(2022, 1, &path), Ok(Fetched::Cached));
+        assert_eq!(*requests.lock().unwrap(), ["GET /2022/day/1/input HTTP/1.1\nCookie: session=53cr3t\n"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn check_client_submit() {
+        let page = "<html><main><article><p>That's not the right answer; your answer is too high.</p></article>";
+        let (config, requests) = mock_server(page);
+        let client = Client::new(&config).unwrap();
+
+        assert_eq!(client.submit(2022, 10, 2, &Answer::from("PAPKFKEJ")), Ok(Outcome::TooHigh));
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["POST /2022/day/10/answer HTTP/1.1\nCookie: session=53cr3t\nlevel=2&answer=PAPKFKEJ"]
+        );
+    }
+
+    #[test]
+    fn check_client_outcomes() {
+        let article = |text| parse_outcome(&format!("<article><p>{}</p></article>", text));
+        assert_eq!(article("That's the right answer!  You are <em>one gold star</em> closer."), Outcome::Correct);
+        assert_eq!(article("That's not the right answer; your answer is too low."), Outcome::TooLow);
+        assert_eq!(article("That's not the right answer.  If you're stuck, ..."), Outcome::Wrong);
+        assert_eq!(article("You don't seem to be solving the right level."), Outcome::AlreadySolved);
+        assert_eq!(
+            article("You gave an answer too recently.  You have 43s left to wait."),
+            Outcome::TooSoon("You gave an answer too recently. You have 43s left to wait.".to_owned())
+        );
+    }
+
+    #[test]
+    fn check_client_config() {
+        let config = Config::parse(r#"{"session": " abc\n", "url": "http://localhost:8080/"}"#).unwrap();
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.url, "http://localhost:8080");
+        assert_eq!(Config::parse("{}"), Ok(Config::default()));
+        assert!(Config::parse(r#"{"session": 12}"#).is_err());
+        assert!(Client::new(&Config::default()).is_err());
+    }
+}
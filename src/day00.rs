@@ -1,3 +1,5 @@
+//! Day 0: an installation check, with a made-up puzzle.
+
 // Example simple puzzle to test you've installed correctly.
 // This will only be run if you specify to run day 0 specifically.  Running all days will skip this script.
 
@@ -6,78 +8,67 @@
 // For Part 2, we're asked to find the square of the difference between the two numbers in each line,
 // then return the sum of those.
 
-// When run with `cargo run 0`, the calling code in main.rs will load the input in the file inputs/0
-// and pass that through as the input to the day00() function here as a single &str.
-// This function (as with all dayXX templates in this repo) returns two Strings, which will be printed
-// out to terminal following the labels "Part 1:" and "Part 2: respectively".
+// When run with `cargo run 0`, the calling code in main.rs will load the input in the file inputs/00/real.txt
+// and pass that through to the parse() function here as a single &str.
+// The parsed input is then handed to part1() and part2() in turn.  Each of these (as with all dayXX
+// templates in this repo) returns an Answer - usually a number, made with `.into()` - which will be
+// printed out to terminal following the labels "Part 1:" and "Part 2:" respectively.
 
-pub fn day00(input_lines: &str) -> (String, String) {
-    let answer1 = input_lines.lines().map(sum_numbers_in_line).sum::<i32>();
-    let answer2 = input_lines
-        .lines()
-        .map(square_difference_in_line)
-        .sum::<i32>();
-    (format!("{}", answer1), format!("{}", answer2))
-}
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
 
-fn sum_numbers_in_line(line: &str) -> i32 {
-    line.split(", ")
-        .map(|number| number.parse::<i32>().expect("Couldn't parse"))
-        .sum::<i32>()
-}
+pub struct Day00;
 
-fn square_difference_in_line(line: &str) -> i32 {
-    let numbers = line
-        .split(", ")
-        .map(|number| number.parse::<i32>().expect("Couldn't parse"))
-        .collect::<Vec<i32>>();
-    assert_eq!(numbers.len(), 2);
-    (numbers.first().unwrap() - numbers.get(1).unwrap()).pow(2)
-}
+crate::register!(Day00);
+
+impl Solution for Day00 {
+    type Input = Vec<Vec<i32>>;
 
-// The template per-day files also come with template UTs.  Most Advent of Code puzzles
-// have one or more examples during the puzzle page which can be useful to run your code
-// against.
+    fn day(&self) -> usize {
+        0
+    }
 
-// There are examples here to test just the output for the first or second part of the
-// the puzzle, or both together.
+    fn title(&self) -> &'static str {
+        "Example"
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // Parsing returns a Result: a ParseError records what was wrong and where, and the runner
+    // turns that into a line and column.
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        input_lines
+            .lines()
+            .map(|line| numbers_in_line(line).map_err(|e| e.within(input_lines, line)))
+            .collect()
+    }
 
-    #[test]
-    fn check_day00_part1_case1() {
-        assert_eq!(
-            day00(
-                "1, 2
-4, 3"
-            )
-            .0,
-            "10".to_string()
-        )
+    fn part1(&self, lines: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1 = lines.iter().map(|numbers| numbers.iter().sum::<i32>()).sum::<i32>();
+        Ok(answer1.into())
     }
 
-    #[test]
-    fn check_day00_part2_case1() {
-        assert_eq!(
-            day00(
-                "1, 2
-4, 3"
-            )
-            .1,
-            "2".to_string()
-        )
+    fn part2(&self, lines: &Self::Input) -> Result<Answer, SolveError> {
+        let answer2 = lines
+            .iter()
+            .map(|numbers| square_difference(numbers))
+            .sum::<Result<i32, SolveError>>()?;
+        Ok(answer2.into())
     }
+}
 
-    #[test]
-    fn check_day00_both_case1() {
-        assert_eq!(
-            day00(
-                "1, 2
-40, 30"
-            ),
-            ("73".to_string(), "101".to_string())
-        )
+fn numbers_in_line(line: &str) -> Result<Vec<i32>, ParseError> {
+    line.split(", ")
+        .map(|number| parse_field(line, number))
+        .collect()
+}
+
+fn square_difference(numbers: &[i32]) -> Result<i32, SolveError> {
+    match numbers {
+        [a, b] => Ok((a - b).pow(2)),
+        _ => Err(SolveError::new(format!("expected 2 numbers on each line, found {}", numbers.len()))),
     }
 }
+
+// Most Advent of Code puzzles have one or more examples on the puzzle page which can be useful
+// to run your code against.  Put each one in the day's inputs directory as exampleN.txt, with
+// its answers in exampleN.expected (see inputs/00), and `cargo test` will run a test for each
+// part of each example.
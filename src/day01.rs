@@ -1,51 +1,47 @@
-pub fn day01(input_lines: &str) -> (String, String) {
-    // Split by double line break to get the elves' entries
-    let lines: Vec<&str> = input_lines.lines().collect();
-    let elves = lines.split(|l| l.is_empty());
-    // Sum up each elf's individual total
-    let mut elf_totals: Vec<i32> = elves.map(|e| e.iter().map(|x| x.trim().parse::<i32>().unwrap()).sum()).collect();
-    elf_totals.sort();
-    elf_totals.reverse();
-    let top_three = &elf_totals[0..3];
-
-    // Part 1: find the elf with the biggest calorie count
-    let answer1 = top_three[0];
-    // Part 2: find the total carried by the top three elves
-    let answer2: i32 = top_three.iter().sum();
-    (format!("{}", answer1), format!("{}", answer2))
-}
+//! Day 1: Calorie Counting.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
 
-    const TEST_INPUT: &str = "1000
-2000
-3000
+pub struct Day01;
 
-4000
+crate::register!(Day01);
 
-5000
-6000
+impl Solution for Day01 {
+    // Each elf's calorie total, biggest first
+    type Input = Vec<i32>;
 
-7000
-8000
-9000
+    fn day(&self) -> usize {
+        1
+    }
 
-10000";
+    fn title(&self) -> &'static str {
+        "Calorie Counting"
+    }
 
-    #[test]
-    fn check_day01_part1_case1() {
-        assert_eq!(day01(TEST_INPUT).0, "24000".to_string())
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        // Split by double line break to get the elves' entries
+        let lines: Vec<&str> = input_lines.lines().collect();
+        let elves = lines.split(|l| l.is_empty());
+        // Sum up each elf's individual total
+        let mut elf_totals: Vec<i32> = elves
+            .map(|e| e.iter().map(|x| parse_field::<i32>(input_lines, x)).sum())
+            .collect::<Result<_, _>>()?;
+        elf_totals.sort();
+        elf_totals.reverse();
+        Ok(elf_totals)
     }
 
-    #[test]
-    fn check_day01_part2_case1() {
-        assert_eq!(day01(TEST_INPUT).1, "45000".to_string())
+    fn part1(&self, elf_totals: &Self::Input) -> Result<Answer, SolveError> {
+        // Part 1: find the elf with the biggest calorie count
+        let answer1 = elf_totals.first().ok_or_else(|| SolveError::new("no elves in the input"))?;
+        Ok((*answer1).into())
     }
 
-    #[test]
-    fn check_day01_both_case1() {
-        assert_eq!(day01(TEST_INPUT), ("24000".to_string(), "45000".to_string()))
+    fn part2(&self, elf_totals: &Self::Input) -> Result<Answer, SolveError> {
+        // Part 2: find the total carried by the top three elves
+        let top_three = elf_totals.get(0..3).ok_or_else(|| SolveError::new("fewer than three elves in the input"))?;
+        let answer2: i32 = top_three.iter().sum();
+        Ok(answer2.into())
     }
 }
@@ -1,167 +1,186 @@
-// pub fn day02_alt(input_lines: &str) -> (String, String) {
-//     // A, X = Rock
-//     // B, Y = Paper
-//     // C, Z = Scissors
-//     let answer1: i32 = input_lines.lines().map(|row| match row.as_str() {
-//         "A X" => 4, // 1 + 3 (Rock + Draw)
-//         "A Y" => 8, // 2 + 6 (Paper + Win)
-//         "A Z" => 3, // 3 + 0 (Scissors + Lose)
-//         "B X" => 1, // 1 + 0
-//         "B Y" => 5, // 2 + 3
-//         "B Z" => 9, // 3 + 6
-//         "C X" => 7, // 1 + 6
-//         "C Y" => 2, // 2 + 0
-//         "C Z" => 6, // 3 + 3
-//         _ => panic!("Invalid combination!")
-//     }).sum();
-
-//     // X = Lose
-//     // Y = Draw
-//     // Z = Win
-//     let answer2: i32 = input_lines.lines().map(|row| match row.as_str() {
-//         "A X" => 3, // 3 + 0
-//         "A Y" => 4, // 1 + 3
-//         "A Z" => 8, // 2 + 6
-//         "B X" => 1, // 1 + 0
-//         "B Y" => 5, // 2 + 3
-//         "B Z" => 9, // 3 + 6
-//         "C X" => 2, // 2 + 0
-//         "C Y" => 6, // 3 + 3
-//         "C Z" => 7, // 1 + 6
-//         _ => panic!("Invalid combination!")
-//     }).sum();
-
-//     (format!("{}", answer1), format!("{}", answer2))
-// }
+//! Day 2: Rock Paper Scissors.
 
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+/// A shape in a round of rock paper scissors, whose value is the score for playing it.
 #[derive(Eq,PartialEq,Ord,PartialOrd,Clone,Copy)]
-enum RPS {
+pub enum Rps {
     Rock = 1,
     Paper = 2,
     Scissors = 3
 }
 
-enum Result {
+enum Outcome {
     Win,
     Lose,
     Draw
 }
 
-fn score_for_type(input: &RPS) -> i32 {
+fn score_for_type(input: &Rps) -> i32 {
     match input {
-        RPS::Rock => 1,
-        RPS::Paper => 2,
-        RPS::Scissors => 3
+        Rps::Rock => 1,
+        Rps::Paper => 2,
+        Rps::Scissors => 3
     }
 }
 
-fn play_rps(tuple: &(RPS, RPS)) -> Result {
+fn play_rps(tuple: &(Rps, Rps)) -> Outcome {
     // There must be some significance to these numbers but I don't know what
     match tuple.1 as i32 - tuple.0 as i32 {
-        -2 => Result::Win,
-        -1 => Result::Lose,
-        0 => Result::Draw,
-        1 => Result::Win,
-        2 => Result::Lose,
+        -2 => Outcome::Win,
+        -1 => Outcome::Lose,
+        0 => Outcome::Draw,
+        1 => Outcome::Win,
+        2 => Outcome::Lose,
         _ => panic!("unexpected result!")
     }
 }
 
-fn points_for_result(result: &Result) -> i32 {
+fn points_for_result(result: &Outcome) -> i32 {
     match result {
-        Result::Win => 6,
-        Result::Lose => 0,
-        Result::Draw => 3
+        Outcome::Win => 6,
+        Outcome::Lose => 0,
+        Outcome::Draw => 3
     }
 }
 
-fn what_to_play(tuple: &(RPS, Result)) -> RPS {
+fn what_to_play(tuple: &(Rps, Outcome)) -> Rps {
     match tuple.0 {
-        RPS::Rock => match tuple.1 {
-            Result::Win => RPS::Paper,
-            Result::Lose => RPS::Scissors,
-            Result::Draw => RPS::Rock
+        Rps::Rock => match tuple.1 {
+            Outcome::Win => Rps::Paper,
+            Outcome::Lose => Rps::Scissors,
+            Outcome::Draw => Rps::Rock
         },
-        RPS::Paper => match tuple.1 {
-            Result::Win => RPS::Scissors,
-            Result::Lose => RPS::Rock,
-            Result::Draw => RPS::Paper
+        Rps::Paper => match tuple.1 {
+            Outcome::Win => Rps::Scissors,
+            Outcome::Lose => Rps::Rock,
+            Outcome::Draw => Rps::Paper
         },
-        RPS::Scissors => match tuple.1 {
-            Result::Win => RPS::Rock,
-            Result::Lose => RPS::Paper,
-            Result::Draw => RPS::Scissors
+        Rps::Scissors => match tuple.1 {
+            Outcome::Win => Rps::Rock,
+            Outcome::Lose => Rps::Paper,
+            Outcome::Draw => Rps::Scissors
         },
     }
 }
 
-pub fn day02(input_lines: &str) -> (String, String) {
-    let answer1: i32 = input_lines
-        .lines()
-        .map(|row| {
-            let theirs = match row.as_bytes()[0] as char {
-                'A' => RPS::Rock,
-                'B' => RPS::Paper,
-                'C' => RPS::Scissors,
-                _ => panic!("Invalid character!")
-            };
-            let mine = match row.as_bytes()[2] as char {
-                'X' => RPS::Rock,
-                'Y' => RPS::Paper,
-                'Z' => RPS::Scissors,
-                _ => panic!("Invalid character!")
-            };
-            (theirs, mine)
-            })
-        .map(|tuple| score_for_type(&tuple.1) + points_for_result(&play_rps(&tuple)))
-        .sum();
-
-    let answer2: i32 = input_lines
-        .lines()
-        .map(|row| {
-            let theirs = match row.as_bytes()[0] as char {
-                'A' => RPS::Rock,
-                'B' => RPS::Paper,
-                'C' => RPS::Scissors,
-                _ => panic!("Invalid character!")
-            };
-            let mine = match row.as_bytes()[2] as char {
-                'X' => Result::Lose,
-                'Y' => Result::Draw,
-                'Z' => Result::Win,
-                _ => panic!("Invalid character!")
-            };
-            (theirs, mine)
+pub struct Day02;
+
+/// The same, but looking up the score for each of the nine possible rounds in a table.
+pub struct Day02Table;
+
+crate::register!("enum" => Day02, "table" => Day02Table);
+
+impl Solution for Day02 {
+    // Their move, and the letter from the second column (whose meaning differs between parts)
+    type Input = Vec<(Rps, char)>;
+
+    fn day(&self) -> usize {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Rock Paper Scissors"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        input_lines
+            .lines()
+            .map(|row| {
+                let (theirs, mine) = row
+                    .split_once(' ')
+                    .ok_or_else(|| ParseError::new(input_lines, row, "expected two letters separated by a space"))?;
+                let theirs = match theirs {
+                    "A" => Rps::Rock,
+                    "B" => Rps::Paper,
+                    "C" => Rps::Scissors,
+                    _ => return Err(ParseError::new(input_lines, theirs, "expected A, B or C"))
+                };
+                let mine = match mine {
+                    "X" | "Y" | "Z" => mine.as_bytes()[0] as char,
+                    _ => return Err(ParseError::new(input_lines, mine, "expected X, Y or Z"))
+                };
+                Ok((theirs, mine))
             })
-        .map(|tuple| score_for_type(&what_to_play(&tuple)) + points_for_result(&tuple.1))
-        .sum();
-    
-    (format!("{}", answer1), format!("{}", answer2))
+            .collect()
+    }
+
+    fn part1(&self, rounds: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1: i32 = rounds
+            .iter()
+            .map(|(theirs, column)| {
+                let mine = match column {
+                    'X' => Rps::Rock,
+                    'Y' => Rps::Paper,
+                    'Z' => Rps::Scissors,
+                    _ => unreachable!("checked when parsing")
+                };
+                (*theirs, mine)
+                })
+            .map(|tuple| score_for_type(&tuple.1) + points_for_result(&play_rps(&tuple)))
+            .sum();
+        Ok(answer1.into())
+    }
+
+    fn part2(&self, rounds: &Self::Input) -> Result<Answer, SolveError> {
+        let answer2: i32 = rounds
+            .iter()
+            .map(|(theirs, column)| {
+                let mine = match column {
+                    'X' => Outcome::Lose,
+                    'Y' => Outcome::Draw,
+                    'Z' => Outcome::Win,
+                    _ => unreachable!("checked when parsing")
+                };
+                (*theirs, mine)
+                })
+            .map(|tuple| score_for_type(&what_to_play(&tuple)) + points_for_result(&tuple.1))
+            .sum();
+        Ok(answer2.into())
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// The score for each round, by `3 * their move + my column`, with A and X as 0.  In part 1, X, Y
+// and Z are rock, paper and scissors; in part 2, they're lose, draw and win.
+const PART1_SCORES: [i32; 9] = [
+    4, 8, 3, // A X: 1 + 3 (Rock + Draw), A Y: 2 + 6 (Paper + Win), A Z: 3 + 0 (Scissors + Lose)
+    1, 5, 9, // B X: 1 + 0, B Y: 2 + 3, B Z: 3 + 6
+    7, 2, 6, // C X: 1 + 6, C Y: 2 + 0, C Z: 3 + 3
+];
+const PART2_SCORES: [i32; 9] = [
+    3, 4, 8, // A X: 3 + 0, A Y: 1 + 3, A Z: 2 + 6
+    1, 5, 9, // B X: 1 + 0, B Y: 2 + 3, B Z: 3 + 6
+    2, 6, 7, // C X: 2 + 0, C Y: 3 + 3, C Z: 1 + 6
+];
 
-    const TEST_INPUT: &str = "A Y
-B X
-C Z";
+impl Solution for Day02Table {
+    // Each round's index into the score tables
+    type Input = Vec<usize>;
+
+    fn day(&self) -> usize {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Rock Paper Scissors"
+    }
 
-    #[test]
-    fn check_day02_part1_case1() {
-        assert_eq!(day02(TEST_INPUT).0, "15".to_string());
-        // assert_eq!(day02_alt(TEST_INPUT).0, "15".to_string());
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        let rounds = Day02.parse(input_lines)?;
+        Ok(rounds
+            .into_iter()
+            .map(|(theirs, column)| 3 * (theirs as usize - 1) + (column as usize - 'X' as usize))
+            .collect())
     }
 
-    #[test]
-    fn check_day02_part2_case1() {
-        assert_eq!(day02(TEST_INPUT).1, "12".to_string());
-        // assert_eq!(day02_alt(TEST_INPUT).1, "12".to_string());
+    fn part1(&self, rounds: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1: i32 = rounds.iter().map(|&round| PART1_SCORES[round]).sum();
+        Ok(answer1.into())
     }
 
-    #[test]
-    fn check_day02_both_case1() {
-        assert_eq!(day02(TEST_INPUT), ("15".to_string(), "12".to_string()));
-        // assert_eq!(day02_alt(TEST_INPUT), ("15".to_string(), "12".to_string()));
+    fn part2(&self, rounds: &Self::Input) -> Result<Answer, SolveError> {
+        let answer2: i32 = rounds.iter().map(|&round| PART2_SCORES[round]).sum();
+        Ok(answer2.into())
     }
 }
@@ -1,5 +1,10 @@
+//! Day 3: Rucksack Reorganization.
+
 use std::collections::HashSet;
 
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
 fn priority_for_char(c: &char) -> i32 {
     match *c {
         'a'..='z' => *c as i32 - 96,
@@ -8,57 +13,63 @@ fn priority_for_char(c: &char) -> i32 {
     }
 }
 
-pub fn day03(input_lines: &str) -> (String, String) {
-    let rucksacks: Vec<&str> = input_lines.lines().collect();
+pub struct Day03;
 
-    let answer1: i32 = rucksacks.iter().map(|r| {
-        let mut chars: Vec<char> = r.chars().collect();
-        let compartment_size = r.len() / 2;
-        let second_half = chars.split_off(compartment_size);
-        let a = HashSet::<char>::from_iter(chars);
-        let b = HashSet::<char>::from_iter(second_half);
-        let mut i = a.intersection(&b);
+crate::register!(Day03);
 
-        priority_for_char(i.next().unwrap())
-    }).sum();
+impl Solution for Day03 {
+    type Input = Vec<Vec<char>>;
 
-    let answer2: i32 = rucksacks.chunks(3).map(|c| {
-        let a = HashSet::<char>::from_iter(c[0].chars());
-        let b = HashSet::<char>::from_iter(c[1].chars());
-        let c = HashSet::<char>::from_iter(c[2].chars());
+    fn day(&self) -> usize {
+        3
+    }
 
-        let i = a.intersection(&b).map(|c| c.to_owned()).collect::<HashSet<char>>();
-        let mut j = i.intersection(&c);
+    fn title(&self) -> &'static str {
+        "Rucksack Reorganization"
+    }
 
-        priority_for_char(j.next().unwrap())
-    }).sum();
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        input_lines.lines().map(|r| {
+            match r.find(|c: char| !c.is_ascii_alphabetic()) {
+                Some(ix) => Err(ParseError::new(input_lines, &r[ix..ix + 1], "items must be letters")),
+                None => Ok(r.chars().collect()),
+            }
+        }).collect()
+    }
 
-    (format!("{}", answer1), format!("{}", answer2))
-}
+    fn part1(&self, rucksacks: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1: i32 = rucksacks.iter().enumerate().map(|(ix, r)| {
+            let mut chars = r.clone();
+            let compartment_size = r.len() / 2;
+            let second_half = chars.split_off(compartment_size);
+            let a = HashSet::<char>::from_iter(chars);
+            let b = HashSet::<char>::from_iter(second_half);
+            let mut i = a.intersection(&b);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+            i.next()
+                .map(priority_for_char)
+                .ok_or_else(|| SolveError::new(format!("no item in both compartments of rucksack {}", ix + 1)))
+        }).sum::<Result<_, _>>()?;
+        Ok(answer1.into())
+    }
 
-    const TEST_INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
+    fn part2(&self, rucksacks: &Self::Input) -> Result<Answer, SolveError> {
+        if rucksacks.len() % 3 != 0 {
+            return Err(SolveError::new(format!("{} rucksacks can't be split into groups of three", rucksacks.len())));
+        }
 
-    #[test]
-    fn check_day03_part1_case1() {
-        assert_eq!(day03(TEST_INPUT).0, "157".to_string())
-    }
+        let answer2: i32 = rucksacks.chunks(3).enumerate().map(|(ix, c)| {
+            let a = HashSet::<&char>::from_iter(c[0].iter());
+            let b = HashSet::<&char>::from_iter(c[1].iter());
+            let c = HashSet::<&char>::from_iter(c[2].iter());
 
-    #[test]
-    fn check_day03_part2_case1() {
-        assert_eq!(day03(TEST_INPUT).1, "70".to_string())
-    }
+            let i = a.intersection(&b).map(|c| c.to_owned()).collect::<HashSet<&char>>();
+            let mut j = i.intersection(&c);
 
-    #[test]
-    fn check_day03_both_case1() {
-        assert_eq!(day03(TEST_INPUT), ("157".to_string(), "70".to_string()))
+            j.next()
+                .map(|c| priority_for_char(c))
+                .ok_or_else(|| SolveError::new(format!("no badge common to group {}", ix + 1)))
+        }).sum::<Result<_, _>>()?;
+        Ok(answer2.into())
     }
 }
@@ -1,48 +1,53 @@
+//! Day 4: Camp Cleanup.
+
 use std::cmp::{max, min};
 
-pub fn day04(input_lines: &str) -> (String, String) {
-    let parsed: Vec<Vec<(i32, i32)>> = input_lines.lines().map(|p| {
-        let elves = p.split(',');
-        elves.map(|e| {
-            let range_ends: Vec<&str> = e.split('-').collect();
-            (range_ends[0].trim().parse().unwrap(), range_ends[1].trim().parse().unwrap())
-        }).collect()
-    }).collect();
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
 
-    let answer1 = parsed.iter().filter(|elf_ranges| {
-        (elf_ranges[0].0 <= elf_ranges[1].0 && elf_ranges[0].1 >= elf_ranges[1].1) ||
-        (elf_ranges[1].0 <= elf_ranges[0].0 && elf_ranges[1].1 >= elf_ranges[0].1)
-    }).count();
+pub struct Day04;
 
-    let answer2 = parsed.iter().filter(|elf_ranges| {
-        max(elf_ranges[0].0, elf_ranges[1].0) <= min(elf_ranges[0].1, elf_ranges[1].1)
-    }).count();
-    (format!("{}", answer1), format!("{}", answer2))
-}
+crate::register!(Day04);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Solution for Day04 {
+    // The pair of section ranges (inclusive) assigned to each pair of elves
+    type Input = Vec<Vec<(i32, i32)>>;
 
-    const TEST_INPUT: &str = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+    fn day(&self) -> usize {
+        4
+    }
 
-    #[test]
-    fn check_day04_part1_case1() {
-        assert_eq!(day04(TEST_INPUT).0, "2".to_string())
+    fn title(&self) -> &'static str {
+        "Camp Cleanup"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        input_lines.lines().map(|p| {
+            let elves: Vec<&str> = p.split(',').collect();
+            if elves.len() != 2 {
+                return Err(ParseError::new(input_lines, p, "expected two ranges separated by a comma"));
+            }
+            elves.iter().map(|e| {
+                let (start, end) = e
+                    .split_once('-')
+                    .ok_or_else(|| ParseError::new(input_lines, e, "expected a range like 2-4"))?;
+                Ok((parse_field(input_lines, start)?, parse_field(input_lines, end)?))
+            }).collect()
+        }).collect()
     }
 
-    #[test]
-    fn check_day04_part2_case1() {
-        assert_eq!(day04(TEST_INPUT).1, "4".to_string())
+    fn part1(&self, parsed: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1 = parsed.iter().filter(|elf_ranges| {
+            (elf_ranges[0].0 <= elf_ranges[1].0 && elf_ranges[0].1 >= elf_ranges[1].1) ||
+            (elf_ranges[1].0 <= elf_ranges[0].0 && elf_ranges[1].1 >= elf_ranges[0].1)
+        }).count();
+        Ok(answer1.into())
     }
 
-    #[test]
-    fn check_day04_both_case1() {
-        assert_eq!(day04(TEST_INPUT), ("2".to_string(), "4".to_string()))
+    fn part2(&self, parsed: &Self::Input) -> Result<Answer, SolveError> {
+        let answer2 = parsed.iter().filter(|elf_ranges| {
+            max(elf_ranges[0].0, elf_ranges[1].0) <= min(elf_ranges[0].1, elf_ranges[1].1)
+        }).count();
+        Ok(answer2.into())
     }
 }
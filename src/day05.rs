@@ -1,88 +1,139 @@
-use std::{num::ParseIntError, str::FromStr};
-use itertools::Itertools;
+//! Day 5: Supply Stacks.
 
-struct Step {
-    qty: usize,
-    from: usize,
-    to: usize
+use std::str::FromStr;
+
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+/// A step of the rearrangement procedure, like `move 1 from 2 to 1`.  Stacks are numbered from 1.
+pub struct Step {
+    pub qty: usize,
+    pub from: usize,
+    pub to: usize
 }
 
 impl std::str::FromStr for Step {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // It's easier if there is only one delimiter
-        let x = s.replace("from", "to");
-
-        let (q, f, t) = x
-            .strip_prefix("move ")
-            .map(|s| s.split(" to "))
-            .map(|s| s.collect_tuple())
-            .unwrap()
-            .unwrap();
+        let words: Vec<&str> = s.split(' ').collect();
+        let (q, f, t) = match words[..] {
+            ["move", q, "from", f, "to", t] => (q, f, t),
+            _ => return Err(ParseError::new(s, s, "expected \"move N from A to B\"")),
+        };
 
-        let qty = q.trim().parse::<usize>()?;
-        let from = f.trim().parse::<usize>()?;
-        let to = t.trim().parse::<usize>()?;
+        let qty = parse_field(s, q)?;
+        let from = parse_field(s, f)?;
+        let to = parse_field(s, t)?;
 
         Ok(Step { qty, from, to })
     }
 }
 
-pub fn day05(input_lines: &str) -> (String, String) {
-    let lines: Vec<&str> = input_lines.lines().collect();
-    // The two halves of the input are split by a blank line
-    let mut parts = lines.split(|l| l.is_empty()).take(2);
-    // First `part` is the diagram of boxes - it is most useful as a Vec<String>, order reversed (so legend is the first item)
-    let boxes: Vec<&str> = parts.next().unwrap().iter().rev().map(|s| s.to_owned()).collect();
-    // The legend (first item in `boxes`) tells us how many stacks we need - then create them
-    let num_stacks: i32 = boxes[0].split_whitespace().last().unwrap().parse().unwrap();
-    let mut stacks: Vec<Vec<char>> = vec![];
-    for _ in 0..num_stacks {
-        stacks.push(vec![]);
+pub struct Day05;
+
+crate::register!(Day05);
+
+impl Solution for Day05 {
+    // The starting stacks (bottom of each stack first), and the rearrangement steps
+    type Input = (Vec<Vec<char>>, Vec<Step>);
+
+    fn day(&self) -> usize {
+        5
     }
 
-    // Parse the stacks - look at the (4j + 1)th character in each row for the box in stack `j`, if any
-    // Our stacks are ordered such that the 'bottom' is the start of the Vec
-    for b in boxes.iter().skip(1) {
-        let row_chars:Vec<char> = b.chars().collect();
-        for j in 0..stacks.len() {
-            let char = row_chars[(4 * j) + 1];
-            if char != ' ' {
-                stacks[j].push(char);
+    fn title(&self) -> &'static str {
+        "Supply Stacks"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        let lines: Vec<&str> = input_lines.lines().collect();
+        // The two halves of the input are split by a blank line
+        let mut parts = lines.split(|l| l.is_empty()).take(2);
+        // First `part` is the diagram of boxes - it is most useful as a Vec<String>, order reversed (so legend is the first item)
+        let boxes: Vec<&str> = parts.next().unwrap().iter().rev().map(|s| s.to_owned()).collect();
+        // The legend (first item in `boxes`) tells us how many stacks we need - then create them
+        let legend = boxes
+            .first()
+            .and_then(|l| l.split_whitespace().last())
+            .ok_or_else(|| ParseError::truncated(input_lines, "missing the drawing of the stacks"))?;
+        let num_stacks: usize = parse_field(input_lines, legend)?;
+        let mut stacks: Vec<Vec<char>> = vec![];
+        for _ in 0..num_stacks {
+            stacks.push(vec![]);
+        }
+
+        // Parse the stacks - look at the (4j + 1)th character in each row for the box in stack `j`, if any
+        // Our stacks are ordered such that the 'bottom' is the start of the Vec
+        for b in boxes.iter().skip(1) {
+            let row_chars:Vec<char> = b.chars().collect();
+            for (j, stack) in stacks.iter_mut().enumerate() {
+                let char = row_chars.get((4 * j) + 1).copied().unwrap_or(' ');
+                if char != ' ' {
+                    stack.push(char);
+                }
             }
         }
+
+        // Parse out the steps - use the FromStr implementation above
+        let steps_raw = parts
+            .next()
+            .ok_or_else(|| ParseError::truncated(input_lines, "missing the rearrangement procedure"))?;
+        let steps: Vec<Step> = steps_raw
+            .iter()
+            .map(|s| Step::from_str(s).map_err(|e| e.within(input_lines, s)))
+            .collect::<Result<_, _>>()?;
+
+        if let Some(step) = steps.iter().zip(steps_raw).find(|(step, _)| {
+            step.from == 0 || step.from > num_stacks || step.to == 0 || step.to > num_stacks
+        }) {
+            return Err(ParseError::new(input_lines, step.1, format!("there are only {} stacks", num_stacks)));
+        }
+
+        Ok((stacks, steps))
     }
 
-    // Parse out the steps - use the FromStr implementation above
-    let steps_raw = parts.next().unwrap();
-    let steps = steps_raw.iter().map(|s| Step::from_str(s).unwrap());
+    fn part1(&self, (stacks, steps): &Self::Input) -> Result<Answer, SolveError> {
+        let mut stacks1 = stacks.clone();
+
+        for (ix, step) in steps.iter().enumerate() {
+            // Part 1: take boxes off one at a time and push them onto the `to` stack
+            for _ in 0..step.qty {
+                let item = stacks1[step.from - 1].pop().ok_or_else(|| empty_stack(ix, step))?;
+                stacks1[step.to - 1].push(item);
+            }
+        }
+        Ok(top_crates(&stacks1).into())
+    }
 
-    // Finally we can solve the puzzle
-    let mut stacks1 = stacks.clone();
-    let mut stacks2 = stacks.clone();
+    fn part2(&self, (stacks, steps): &Self::Input) -> Result<Answer, SolveError> {
+        let mut stacks2 = stacks.clone();
 
-    for (_, step) in steps.enumerate() {
-        // Part 1: take boxes off one at a time and push them onto the `to` stack
-        for _ in 0..step.qty {
-            let item = stacks1[step.from - 1].pop().unwrap();
-            stacks1[step.to - 1].push(item);
+        for (ix, step) in steps.iter().enumerate() {
+            // Part 2: take a slice off the `from` stack and append it to the `to` stack
+            let from_stack = stacks2[step.from - 1].clone();
+            let split = from_stack.len().checked_sub(step.qty).ok_or_else(|| empty_stack(ix, step))?;
+            let (remainder, slice) = from_stack.split_at(split);
+            stacks2[step.from - 1] = remainder.to_vec();
+            stacks2[step.to - 1].extend_from_slice(slice);
         }
+        Ok(top_crates(&stacks2).into())
+    }
+}
+
+fn empty_stack(ix: usize, step: &Step) -> SolveError {
+    SolveError::new(format!("step {} moves {} crates from stack {}, which runs out", ix + 1, step.qty, step.from))
+}
 
-        // Part 2: take a slice off the `from` stack and append it to the `to` stack
-        let from_stack = stacks2[step.from - 1].clone();
-        let (remainder, slice) = from_stack.split_at(from_stack.len() - step.qty);
-        stacks2[step.from - 1] = remainder.to_vec();
-        stacks2[step.to - 1].extend_from_slice(slice);
-    };
-    let answer1 = stacks1.iter().fold("".to_string(), |acc, x| format!("{}{}", acc, x.last().unwrap()));
-    let answer2 = stacks2.iter().fold("".to_string(), |acc, x| format!("{}{}", acc, x.last().unwrap()));
-    (answer1, answer2)
+// The crate on top of each stack (or a space for an empty stack)
+fn top_crates(stacks: &[Vec<char>]) -> String {
+    stacks.iter().fold("".to_string(), |acc, x| format!("{}{}", acc, x.last().unwrap_or(&' ')))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::Solver;
 
     const TEST_INPUT: &str = "    [D]    
 [N] [C]    
@@ -96,17 +147,15 @@ move 1 from 1 to 2
 ";
 
     #[test]
-    fn check_day05_part1_case1() {
-        assert_eq!(day05(TEST_INPUT).0, "CMZ".to_string())
-    }
-
-    #[test]
-    fn check_day05_part2_case1() {
-        assert_eq!(day05(TEST_INPUT).1, "MCD".to_string())
+    fn check_day05_bad_step() {
+        let input = TEST_INPUT.replace("move 3 from 1", "move three from 1");
+        let err = Day05.parse_any(&input).unwrap_err();
+        assert_eq!(err.to_string(), "line 7, column 6: expected usize (invalid digit found in string): \"three\"");
     }
 
     #[test]
-    fn check_day05_both_case1() {
-        assert_eq!(day05(TEST_INPUT), ("CMZ".to_string(), "MCD".to_string()))
+    fn check_day05_truncated() {
+        let err = Day05.parse_any("    [D]\n[N] [C]\n 1   2 \n").unwrap_err();
+        assert_eq!(err.to_string(), "line 4, column 1: missing the rearrangement procedure (input ended early)");
     }
 }
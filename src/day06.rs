@@ -1,47 +1,51 @@
+//! Day 6: Tuning Trouble.
+
 use std::collections::HashSet;
 
-fn find_first_unique_set(input: &str, len: usize) -> usize {
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+fn find_first_unique_set(input: &str, len: usize) -> Result<usize, SolveError> {
     for i in len-1..input.len() { // start at char #4
         let start_ix = i - (len - 1);
         let select_chars = input[start_ix..=i].to_owned();
         let charset: HashSet<char> = select_chars.chars().collect();
         if charset.len() == len {
-            return i + 1;
+            return Ok(i + 1);
         }
     }
-    unreachable!()
+    Err(SolveError::new(format!("no run of {} different characters in the datastream", len)))
 }
 
-pub fn day06(input_line: &str) -> (String, String) {
-    let answer1 = find_first_unique_set(input_line, 4);
-    let answer2 = find_first_unique_set(input_line, 14);
-    (format!("{}", answer1), format!("{}", answer2))
-}
+pub struct Day06;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn check_day06_part1_case1() {
-        assert_eq!(day06("mjqjpqmgbljsphdztnvjfqwrcgsmlb").0, "7".to_string());
-        assert_eq!(day06("bvwbjplbgvbhsrlpgdmjqwftvncz").0, "5".to_string());
-        assert_eq!(day06("nppdvjthqldpwncqszvftbrmjlhg").0, "6".to_string());
-        assert_eq!(day06("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").0, "10".to_string());
-        assert_eq!(day06("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").0, "11".to_string());
+crate::register!(Day06);
+
+impl Solution for Day06 {
+    type Input = String;
+
+    fn day(&self) -> usize {
+        6
     }
 
-    #[test]
-    fn check_day06_part2_case1() {
-        assert_eq!(day06("mjqjpqmgbljsphdztnvjfqwrcgsmlb").1, "19".to_string());
-        assert_eq!(day06("bvwbjplbgvbhsrlpgdmjqwftvncz").1, "23".to_string());
-        assert_eq!(day06("nppdvjthqldpwncqszvftbrmjlhg").1, "23".to_string());
-        assert_eq!(day06("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").1, "29".to_string());
-        assert_eq!(day06("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").1, "26".to_string());
+    fn title(&self) -> &'static str {
+        "Tuning Trouble"
     }
 
-    // #[test]
-    // fn check_day06_both_case1() {
-    //     assert_eq!(day06(""), ("0".to_string(), "0".to_string()))
-    // }
+    fn parse(&self, input_line: &str) -> Result<Self::Input, ParseError> {
+        match input_line.find(|c: char| !c.is_ascii_lowercase()) {
+            Some(ix) => Err(ParseError::new(input_line, &input_line[ix..ix + 1], "the datastream must be lowercase letters")),
+            None => Ok(input_line.to_owned()),
+        }
+    }
+
+    fn part1(&self, input_line: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1 = find_first_unique_set(input_line, 4)?;
+        Ok(answer1.into())
+    }
+
+    fn part2(&self, input_line: &Self::Input) -> Result<Answer, SolveError> {
+        let answer2 = find_first_unique_set(input_line, 14)?;
+        Ok(answer2.into())
+    }
 }
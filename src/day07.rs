@@ -1,11 +1,63 @@
+//! Day 7: No Space Left On Device.
+
 use std::collections::HashMap;
 
-struct DirectoryNode {
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+/// A directory in the filesystem, with the total size of the files directly inside it, and the
+/// full paths of its subdirectories.
+pub struct DirectoryNode {
     size: i64,
     children: Vec<String>,
 }
 
-pub fn day07(input_lines: &str) -> (String, String) {
+pub struct Day07;
+
+crate::register!(Day07);
+
+impl Solution for Day07 {
+    // Every directory, keyed by its full path
+    type Input = HashMap<String, DirectoryNode>;
+
+    fn day(&self) -> usize {
+        7
+    }
+
+    fn title(&self) -> &'static str {
+        "No Space Left On Device"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        build_tree(input_lines)
+    }
+
+    fn part1(&self, dir_list: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1: i64 = dir_list.values().filter(|v| {
+            v.size <= 100000 && !v.children.is_empty()
+        }).map(|v| v.size).sum();
+        Ok(answer1.into())
+    }
+
+    fn part2(&self, dir_list: &Self::Input) -> Result<Answer, SolveError> {
+        let total: i64 = 70000000;
+        let target: i64 = 30000000;
+        let starting = dir_list["/"].size;
+        let gap = target - (total - starting);
+
+        log::debug!("/ is {} so need {}", starting, gap);
+
+        let answer2 = dir_list
+            .values()
+            .map(|v| v.size)
+            .filter(|x| *x > gap)
+            .min()
+            .ok_or_else(|| SolveError::new(format!("no directory is big enough to free up {}", gap)))?;
+        Ok(answer2.into())
+    }
+}
+
+fn build_tree(input_lines: &str) -> Result<HashMap<String, DirectoryNode>, ParseError> {
     let root = DirectoryNode {
         size: 0,
         children: Vec::new(),
@@ -21,12 +73,16 @@ pub fn day07(input_lines: &str) -> (String, String) {
             continue;
         } else if !line.starts_with('$') {
             // Must be listing stuff, so add it to the current point in the tree
-            let (first, name) = line.split_once(' ').unwrap();
+            let (first, name) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError::new(input_lines, line, "expected a size or \"dir\", then a name"))?;
             let size = match first {
                 "dir" => 0,
-                _ => first.trim().parse().unwrap()
+                _ => parse_field(input_lines, first)?
             };
-            let current_dir = dir_list.get_mut(&current).unwrap();
+            let current_dir = dir_list
+                .get_mut(&current)
+                .ok_or_else(|| ParseError::new(input_lines, line, format!("{} wasn't listed by its parent", current)))?;
             let full_path = format!("{}/{}", current, name);
 
             if !current_dir.children.contains(&full_path) {
@@ -44,7 +100,9 @@ pub fn day07(input_lines: &str) -> (String, String) {
         } else if line.starts_with("$ cd") {
             let (_, target) = line.split_at(5);
             if target == ".." {
-                let (new, _) = current.rsplit_once('/').unwrap();
+                let (new, _) = current
+                    .rsplit_once('/')
+                    .ok_or_else(|| ParseError::new(input_lines, line, "can't go up from the root directory"))?;
                 current = new.to_string();
                 previous.pop();
             } else {
@@ -54,68 +112,10 @@ pub fn day07(input_lines: &str) -> (String, String) {
         }
     }
 
-    // Debug: print it out
     for (key, val) in dir_list.iter() {
-        println!("{}, size {}", key, val.size);
-        // println!("children: {:?}", val.children);
+        log::debug!("{}, size {}", key, val.size);
+        log::trace!("children: {:?}", val.children);
     }
 
-    let answer1: i64 = dir_list.iter().filter(|(_, v)| {
-        v.size <= 100000 && !v.children.is_empty()
-    }).map(|(_, v)| v.size).sum();
-
-    let total: i64 = 70000000;
-    let target: i64 = 30000000;
-    let starting = dir_list["/"].size;
-    let gap = target - (total - starting);
-
-    println!("/ is {} so need {}", starting, gap);
-
-    let answer2 = dir_list.iter().map(|(_, v)| v.size).filter(|x| *x > gap).min().unwrap();
-
-    (format!("{}", answer1), format!("{}", answer2))
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
-
-    #[test]
-    fn check_day07_part1_case1() {
-        assert_eq!(day07(TEST_INPUT).0, "95437".to_string())
-    }
-
-    #[test]
-    fn check_day07_part2_case1() {
-        assert_eq!(day07(TEST_INPUT).1, "24933642".to_string())
-    }
-
-    #[test]
-    fn check_day07_both_case1() {
-        assert_eq!(day07(TEST_INPUT), ("95437".to_string(), "24933642".to_string()))
-    }
+    Ok(dir_list)
 }
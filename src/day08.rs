@@ -1,3 +1,8 @@
+//! Day 8: Treetop Tree House.
+
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
 fn visible<'a>(trees: impl Iterator<Item = &'a i8>, height: i8) -> (bool, i32) {
     let mut seen_from_edge = true;
     let mut trees_visible = 0;
@@ -17,44 +22,88 @@ fn visible<'a>(trees: impl Iterator<Item = &'a i8>, height: i8) -> (bool, i32) {
     (seen_from_edge, trees_visible)
 }
 
-pub fn day08(input_lines: &str) -> (String, String) {
-    const SIZE: usize = 99;
-    let mut grid = [[-1_i8; SIZE]; SIZE];
+const SIZE: usize = 99;
 
-    for (y, line) in input_lines.lines().enumerate() {
-        for (x, cell) in line.chars().enumerate() {
-            grid[x][y] = cell.to_digit(10).unwrap() as i8;
-        }
+// Tree heights indexed by [x][y], with -1 marking empty space beyond the edge of a smaller grid
+type Grid = [[i8; SIZE]; SIZE];
+
+pub struct Day08;
+
+crate::register!(Day08);
+
+impl Solution for Day08 {
+    type Input = Box<Grid>;
+
+    fn day(&self) -> usize {
+        8
     }
 
-    let mut visible_trees = 0;
-    let mut best_scenic_score = 0;
+    fn title(&self) -> &'static str {
+        "Treetop Tree House"
+    }
 
-    for x in 0..SIZE {
-        for y in 0..SIZE {
-            let tree = grid[x][y];
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        let mut grid = Box::new([[-1_i8; SIZE]; SIZE]);
 
-            if tree == -1 { continue }; // empty space
+        for (y, line) in input_lines.lines().enumerate() {
+            if y >= SIZE || line.len() > SIZE {
+                return Err(ParseError::new(input_lines, line, format!("the forest can be at most {} trees square", SIZE)));
+            }
+            for (x, cell) in line.char_indices() {
+                grid[x][y] = cell
+                    .to_digit(10)
+                    .ok_or_else(|| ParseError::new(input_lines, &line[x..x + cell.len_utf8()], "tree heights must be digits"))?
+                    as i8;
+            }
+        }
+
+        Ok(grid)
+    }
 
-            let (above_visible, above_dist) = visible(grid[x][..y].iter().rev(), tree);
-            let (below_visible, below_dist) = visible(grid[x][y+1..].iter(), tree);
-            let (left_visible, left_dist) = visible(grid[..x].iter().rev().map(|z| &z[y]), tree);
-            let (right_visible, right_dist) = visible(grid[x+1..].iter().map(|z| &z[y]), tree);
+    fn part1(&self, grid: &Self::Input) -> Result<Answer, SolveError> {
+        let mut visible_trees = 0;
 
-            if above_visible || below_visible || left_visible || right_visible {
+        for_each_tree(grid, |views| {
+            if views.iter().any(|(seen_from_edge, _)| *seen_from_edge) {
                 visible_trees += 1;
             }
+        });
+
+        let answer1 = visible_trees;
+        Ok(answer1.into())
+    }
 
-            let scenic_score = above_dist * below_dist * left_dist * right_dist;
+    fn part2(&self, grid: &Self::Input) -> Result<Answer, SolveError> {
+        let mut best_scenic_score = 0;
+
+        for_each_tree(grid, |views| {
+            let scenic_score: i32 = views.iter().map(|(_, dist)| dist).product();
             if scenic_score > best_scenic_score {
                 best_scenic_score = scenic_score;
             }
-        }
+        });
+
+        let answer2 = best_scenic_score;
+        Ok(answer2.into())
     }
+}
+
+// Call `f` with the view (above, below, left, right) from every tree in the grid
+fn for_each_tree(grid: &Grid, mut f: impl FnMut([(bool, i32); 4])) {
+    for x in 0..SIZE {
+        for y in 0..SIZE {
+            let tree = grid[x][y];
+
+            if tree == -1 { continue }; // empty space
 
-    let answer1 = visible_trees;
-    let answer2 = best_scenic_score;
-    (format!("{}", answer1), format!("{}", answer2))
+            f([
+                visible(grid[x][..y].iter().rev(), tree),
+                visible(grid[x][y+1..].iter(), tree),
+                visible(grid[..x].iter().rev().map(|z| &z[y]), tree),
+                visible(grid[x+1..].iter().map(|z| &z[y]), tree),
+            ]);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -62,30 +111,9 @@ mod tests {
     use super::*;
     use crate::helpers::load_input;
 
-    const TEST_INPUT : &str = "30373
-25512
-65332
-33549
-35390";
-
-    #[test]
-    fn check_day08_part1_case1() {
-        assert_eq!(day08(TEST_INPUT).0, "21".to_string())
-    }
-
-    #[test]
-    fn check_day08_part2_case1() {
-        assert_eq!(day08(TEST_INPUT).1, "8".to_string())
-    }
-
-    #[test]
-    fn check_day08_both_case1() {
-        assert_eq!(day08(TEST_INPUT), ("21".to_string(), "8".to_string()))
-    }
-
     #[test]
     fn check_day08_puzzle() {
         let input = load_input(8);
-        assert_eq!(day08(&input), ("1832".to_string(), "157320".to_string()))
+        assert_eq!(Day08.solve(&input), (Answer::Int(1832), Answer::Int(157320)))
     }
 }
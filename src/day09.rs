@@ -1,4 +1,9 @@
-use std::{str::FromStr, num::ParseIntError, collections::HashSet};
+//! Day 9: Rope Bridge.
+
+use std::{str::FromStr, collections::HashSet};
+
+use crate::error::{parse_field, parse_lines, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
 
 enum Direction {
     Up,
@@ -7,25 +12,27 @@ enum Direction {
     Right,
 }
 
-struct Instruction {
+/// One motion of the head of the rope, like `R 4`.
+pub struct Instruction {
     dir: Direction,
     dist: u8,
 }
 
 impl FromStr for Instruction {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // It's easier if there is only one delimiter
-        let (a, b) = s.split_once(' ').unwrap();
+        let (a, b) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(s, s, "expected a direction and a distance"))?;
         let dir = match a {
             "U" => Direction::Up,
             "D" => Direction::Down,
             "L" => Direction::Left,
             "R" => Direction::Right,
-            _ => unreachable!()
+            _ => return Err(ParseError::new(s, a, "direction must be U, D, L or R"))
         };
-        let dist: u8 = b.parse()?;
+        let dist: u8 = parse_field(s, b)?;
 
         Ok(Instruction { dir, dist })
     }
@@ -80,77 +87,73 @@ fn move_rope(rope: &mut [Point], direction: &Direction) {
     }
 }
 
-pub fn day09(input_lines: &str) -> (String, String) {
-    let instructions: Vec<Instruction> = input_lines.lines().map(|s| Instruction::from_str(s).unwrap()).collect();
-
-    let mut cells_visited_a: HashSet<Point> = HashSet::new();
-    let mut cells_visited_b: HashSet<Point> = HashSet::new();
+// Follow the instructions with a rope of `N` knots, and count the cells visited by the tail
+fn count_tail_cells<const N: usize>(instructions: &[Instruction]) -> usize {
+    let mut cells_visited: HashSet<Point> = HashSet::new();
 
-    let mut rope_a = [Point {x: 0, y:0}; 2];
-    cells_visited_a.insert(*rope_a.last().unwrap());
-
-    let mut rope_b = [Point {x: 0, y:0}; 10];
-    cells_visited_b.insert(*rope_b.last().unwrap());
+    let mut rope = [Point {x: 0, y:0}; N];
+    cells_visited.insert(*rope.last().unwrap());
 
     for instruction in instructions.iter() {
         for _ in 0..instruction.dist {
-            move_rope(&mut rope_a, &instruction.dir);
-            move_rope(&mut rope_b, &instruction.dir);
+            move_rope(&mut rope, &instruction.dir);
 
             // Record the position of the tail
-            cells_visited_a.insert(*rope_a.last().unwrap());
-            cells_visited_b.insert(*rope_b.last().unwrap());
+            cells_visited.insert(*rope.last().unwrap());
         }
     }
 
-    let answer1 = cells_visited_a.len();
-    let answer2 = cells_visited_b.len();
-    (format!("{}", answer1), format!("{}", answer2))
+    cells_visited.len()
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::helpers::load_input;
+pub struct Day09;
 
-    use super::*;
+crate::register!(Day09);
 
-    const TEST_INPUT: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
+impl Solution for Day09 {
+    type Input = Vec<Instruction>;
 
-    #[test]
-    fn check_day09_part1_case1() {
-        assert_eq!(day09(TEST_INPUT).0, "13".to_string())
+    fn day(&self) -> usize {
+        9
     }
 
-    #[test]
-    fn check_day09_part2_case1() {
-        assert_eq!(day09(TEST_INPUT).1, "1".to_string());
-
-        let test_input_2 = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20";
-        assert_eq!(day09(test_input_2).1, "36".to_string());
+    fn title(&self) -> &'static str {
+        "Rope Bridge"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        parse_lines(input_lines)
     }
 
+    fn part1(&self, instructions: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1 = count_tail_cells::<2>(instructions);
+        Ok(answer1.into())
+    }
+
+    fn part2(&self, instructions: &Self::Input) -> Result<Answer, SolveError> {
+        let answer2 = count_tail_cells::<10>(instructions);
+        Ok(answer2.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::load_input;
+    use crate::solution::Solver;
+
+    use super::*;
+
     #[test]
-    fn check_day09_both_case1() {
-        assert_eq!(day09(TEST_INPUT), ("13".to_string(), "1".to_string()))
+    fn check_day09_bad_direction() {
+        let err = Day09.parse_any("R 4\nU 4\nX 3").unwrap_err();
+        assert_eq!(err.to_string(), "line 3, column 1: direction must be U, D, L or R: \"X\"");
+        let err = Day09.parse_any("R 4\nU").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 1: expected a direction and a distance: \"U\"");
     }
 
     #[test]
     fn check_day09_puzzle() {
         let input = load_input(9);
-        assert_eq!(day09(&input), ("5930".to_string(), "2443".to_string()))
+        assert_eq!(Day09.solve(&input), (Answer::Int(5930), Answer::Int(2443)))
     }
 }
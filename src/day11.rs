@@ -1,7 +1,10 @@
-use std::{str::FromStr, num::ParseIntError};
+//! Day 11: Monkey in the Middle.
 
-use lazy_static::lazy_static;
-use regex::Regex;
+use std::str::{FromStr, Lines};
+
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::helpers::blocks;
+use crate::solution::{Answer, Solution};
 
 #[derive(Clone)]
 enum Operand {
@@ -9,8 +12,10 @@ enum Operand {
     Num(i64)
 }
 
+/// A monkey's notes: the items it holds, how it changes their worry levels, and where it throws
+/// them next.
 #[derive(Clone)]
-struct Monkey {
+pub struct Monkey {
     items: Vec<i64>,
     operation: String,
     operand: Operand,
@@ -20,35 +25,89 @@ struct Monkey {
     items_inspected: i64,
 }
 
+// The rest of the next line of `block`, which should start with `prefix`
+fn field<'a>(block: &'a str, lines: &mut Lines<'a>, prefix: &str) -> Result<&'a str, ParseError> {
+    let expected = || format!("expected \"{}\"", prefix.trim());
+    let line = lines.next().ok_or_else(|| ParseError::truncated(block, expected()))?;
+    line.strip_prefix(prefix).ok_or_else(|| ParseError::new(block, line, expected()))
+}
+
 impl FromStr for Monkey {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"Monkey (\d):\n  Starting items: (.*)\n  Operation: new = old (.) (.*)\n  Test: divisible by (\d+)\n    If true: throw to monkey (\d)\n    If false: throw to monkey (\d)").unwrap();
+        let mut lines = s.lines();
+
+        let name = field(s, &mut lines, "Monkey ")?;
+        if !name.ends_with(':') {
+            return Err(ParseError::new(s, name, "expected \"Monkey N:\""));
         }
-        
-        let caps = RE.captures(s).unwrap();
-        let items: Vec<i64> = caps.get(2).unwrap().as_str().split(", ").map(|x| x.parse().unwrap()).collect();
-        let operation = caps.get(3).unwrap().as_str().to_string();
-        let operand = match caps.get(4).unwrap().as_str() {
+        let items = field(s, &mut lines, "  Starting items: ")?
+            .split(", ")
+            .map(|x| parse_field(s, x))
+            .collect::<Result<Vec<i64>, _>>()?;
+        let formula = field(s, &mut lines, "  Operation: new = old ")?;
+        let (operation, operand) = formula
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(s, formula, "expected an operator and an operand"))?;
+        if !matches!(operation, "+" | "-" | "*") {
+            return Err(ParseError::new(s, operation, "operator must be +, - or *"));
+        }
+        let operation = operation.to_string();
+        let operand = match operand {
             "old" => Operand::Old,
-            other => Operand::Num(other.parse().unwrap()),
+            other => Operand::Num(parse_field(s, other)?),
         };
-        let test_divisor = caps.get(5).unwrap().as_str().parse()?;
-        let next_if_true = caps.get(6).unwrap().as_str().parse()?;
-        let next_if_false = caps.get(7).unwrap().as_str().parse()?;
+        let test_divisor = parse_field(s, field(s, &mut lines, "  Test: divisible by ")?)?;
+        let next_if_true = parse_field(s, field(s, &mut lines, "    If true: throw to monkey ")?)?;
+        let next_if_false = parse_field(s, field(s, &mut lines, "    If false: throw to monkey ")?)?;
 
         Ok(Monkey { items, operation, operand, test_divisor, next_if_true, next_if_false, items_inspected: 0 })
     }
 }
 
-pub fn day11(input_lines: &str) -> (String, String) {
-    let lines = input_lines.lines().collect::<Vec<&str>>();
-    let blocks = lines.split(|l| l.is_empty());
-    let monkeys: Vec<Monkey> = blocks.map(|b| Monkey::from_str(b.join("\n").as_str()).unwrap()).collect();
+pub struct Day11;
+
+crate::register!(Day11);
+
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+
+    fn day(&self) -> usize {
+        11
+    }
+
+    fn title(&self) -> &'static str {
+        "Monkey in the Middle"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        let monkeys: Vec<(&str, Monkey)> = blocks(input_lines)
+            .map(|b| Monkey::from_str(b).map(|m| (b, m)).map_err(|e| e.within(input_lines, b)))
+            .collect::<Result<_, _>>()?;
+
+        for (block, monkey) in &monkeys {
+            if monkey.next_if_true as usize >= monkeys.len() || monkey.next_if_false as usize >= monkeys.len() {
+                return Err(ParseError::new(input_lines, block, format!("there are only {} monkeys", monkeys.len())));
+            }
+        }
+
+        Ok(monkeys.into_iter().map(|(_, m)| m).collect())
+    }
+
+    fn part1(&self, monkeys: &Self::Input) -> Result<Answer, SolveError> {
+        let answer1 = part1(monkeys);
+        Ok(answer1.into())
+    }
+
+    fn part2(&self, monkeys: &Self::Input) -> Result<Answer, SolveError> {
+        let answer2 = part2(monkeys);
+        Ok(answer2.into())
+    }
+}
 
-    let mut monkeys_1 = monkeys.clone();
+fn part1(monkeys: &[Monkey]) -> i64 {
+    let mut monkeys_1 = monkeys.to_vec();
 
     for _ in 1..=20 {
         for m in 0..monkeys_1.len() {
@@ -80,10 +139,12 @@ pub fn day11(input_lines: &str) -> (String, String) {
         }
     }
 
-    monkeys_1.sort_by(|m, n| m.items_inspected.cmp(&n.items_inspected));
-    let answer1 = monkeys_1.iter().rev().take(2).fold(1, |acc, m| acc * m.items_inspected);
+    monkeys_1.sort_by_key(|m| m.items_inspected);
+    monkeys_1.iter().rev().take(2).fold(1, |acc, m| acc * m.items_inspected)
+}
 
-    let mut monkeys_2 = monkeys;
+fn part2(monkeys: &[Monkey]) -> i64 {
+    let mut monkeys_2 = monkeys.to_vec();
     let divisor = monkeys_2.iter().fold(1, |acc, m| acc * m.test_divisor);
     for _ in 1..=10000 {
         for m in 0..monkeys_2.len() {
@@ -113,14 +174,14 @@ pub fn day11(input_lines: &str) -> (String, String) {
         }
     }
 
-    monkeys_2.sort_by(|m, n| m.items_inspected.cmp(&n.items_inspected));
-    let answer2 = monkeys_2.iter().rev().take(2).fold(1, |acc, m| acc * m.items_inspected);
-    (format!("{}", answer1), format!("{}", answer2))
+    monkeys_2.sort_by_key(|m| m.items_inspected);
+    monkeys_2.iter().rev().take(2).fold(1, |acc, m| acc * m.items_inspected)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::Solver;
 
     const TEST_INPUT: &str = "Monkey 0:
   Starting items: 79, 98
@@ -151,17 +212,12 @@ Monkey 3:
     If false: throw to monkey 1";
 
     #[test]
-    fn check_day11_part1_case1() {
-        assert_eq!(day11(TEST_INPUT).0, "10605".to_string())
-    }
+    fn check_day11_bad_monkey() {
+        let input = TEST_INPUT.replace("new = old + 6", "new = old / 6");
+        let err = Day11.parse_any(&input).unwrap_err();
+        assert_eq!(err.to_string(), "line 10, column 24: operator must be +, - or *: \"/\"");
 
-    #[test]
-    fn check_day11_part2_case1() {
-        assert_eq!(day11(TEST_INPUT).1, "2713310158".to_string())
-    }
-
-    #[test]
-    fn check_day11_both_case1() {
-        assert_eq!(day11(TEST_INPUT), ("10605".to_string(), "2713310158".to_string()))
+        let err = Day11.parse_any(&TEST_INPUT[..78]).unwrap_err();
+        assert_eq!(err.to_string(), "line 4, column 1: expected \"Test: divisible by\": \"  Test: divisib\"");
     }
 }
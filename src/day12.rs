@@ -1,5 +1,10 @@
+//! Day 12: Hill Climbing Algorithm.
+
 use std::collections::{VecDeque, HashSet};
 
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
 type Point = (usize, usize);
 type Grid = Vec<Vec<i32>>;
 
@@ -18,7 +23,7 @@ fn try_to_step(
     }
 }
 
-fn main(grid: &Grid, start: &Point, end: &Point, part_two: bool) -> usize {
+fn main(grid: &Grid, start: &Point, end: &Point, part_two: bool) -> Result<usize, SolveError> {
     let mut queue = VecDeque::<Point>::new();
     let mut visited_squares = HashSet::<Point>::new();
     let mut steps = 0;
@@ -53,61 +58,66 @@ fn main(grid: &Grid, start: &Point, end: &Point, part_two: bool) -> usize {
         steps += 1;
         
         if steps > grid.len() * grid[0].len() {
-            panic!("too many steps");
+            return Err(SolveError::new("too many steps"));
         }
     }
 
-    steps
+    Ok(steps)
 }
 
-pub fn day12(input_lines: &str) -> (String, String) {
-    let mut grid: Grid = Vec::new();
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-
-    for (y, line) in input_lines.lines().enumerate() {
-        grid.push(line.chars().enumerate().map(|(x, c)| {
-            match c {
-                'S' => {
-                    start = (x, y);
-                    'a' as i32
-                },
-                'E' => {
-                    end = (x, y);
-                    'z' as i32
-                },
-                _ => c as i32,
-            }
-        }).collect());
+pub struct Day12;
+
+crate::register!(Day12);
+
+impl Solution for Day12 {
+    // The height map, the start point and the end point
+    type Input = (Grid, Point, Point);
+
+    fn day(&self) -> usize {
+        12
     }
 
-    let answer1 = main(&grid, &start, &end, false);
-    let answer2 = main(&grid, &start, &end, true);
-    (format!("{}", answer1), format!("{}", answer2))
-}
+    fn title(&self) -> &'static str {
+        "Hill Climbing Algorithm"
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        let mut grid: Grid = Vec::new();
+        let mut start = None;
+        let mut end = None;
 
-    const TEST_INPUT: &str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
+        for (y, line) in input_lines.lines().enumerate() {
+            if y > 0 && line.len() != grid[0].len() {
+                return Err(ParseError::new(input_lines, line, "every row of the map must be the same length"));
+            }
+            grid.push(line.char_indices().map(|(x, c)| {
+                match c {
+                    'S' => {
+                        start = Some((x, y));
+                        Ok('a' as i32)
+                    },
+                    'E' => {
+                        end = Some((x, y));
+                        Ok('z' as i32)
+                    },
+                    'a'..='z' => Ok(c as i32),
+                    _ => Err(ParseError::new(input_lines, &line[x..x + c.len_utf8()], "heights must be a-z")),
+                }
+            }).collect::<Result<_, _>>()?);
+        }
 
-    #[test]
-    fn check_day12_part1_case1() {
-        assert_eq!(day12(TEST_INPUT).0, "31".to_string())
+        let start = start.ok_or_else(|| ParseError::truncated(input_lines, "the map has no start point (S)"))?;
+        let end = end.ok_or_else(|| ParseError::truncated(input_lines, "the map has no end point (E)"))?;
+        Ok((grid, start, end))
     }
 
-    #[test]
-    fn check_day12_part2_case1() {
-        assert_eq!(day12(TEST_INPUT).1, "29".to_string())
+    fn part1(&self, (grid, start, end): &Self::Input) -> Result<Answer, SolveError> {
+        let answer1 = main(grid, start, end, false)?;
+        Ok(answer1.into())
     }
 
-    #[test]
-    fn check_day12_both_case1() {
-        assert_eq!(day12(TEST_INPUT), ("31".to_string(), "29".to_string()))
+    fn part2(&self, (grid, start, end): &Self::Input) -> Result<Answer, SolveError> {
+        let answer2 = main(grid, start, end, true)?;
+        Ok(answer2.into())
     }
 }
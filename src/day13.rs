@@ -1,8 +1,41 @@
+//! Day 13: Distress Signal.
+
+use std::str::FromStr;
+
 use itertools::Itertools;
 use serde_json::Value;
 
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+/// A distress signal packet: a list whose items are integers or lists.
+///
+/// Packets are ordered as the puzzle describes, so a pair is in the right order if `left < right`.
+/// They parse from the puzzle's notation, e.g. `[1,[2,[3]]]`.
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Packet(serde_json::Value);
+pub struct Packet(serde_json::Value);
+
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value: Value = serde_json::from_str(s).map_err(|e| {
+            let bad = s.get(e.column().saturating_sub(1)..).unwrap_or_default();
+            ParseError::new(s, bad, format!("invalid packet ({})", e))
+        })?;
+        check_packet(s, &value)?;
+        Ok(Packet(value))
+    }
+}
+
+// Packets may only contain lists and non-negative integers
+fn check_packet(s: &str, value: &Value) -> Result<(), ParseError> {
+    match value {
+        Value::Array(items) => items.iter().try_for_each(|v| check_packet(s, v)),
+        Value::Number(n) if n.is_u64() => Ok(()),
+        other => Err(ParseError::new(s, s, format!("packets can't contain {}", other))),
+    }
+}
 
 impl PartialOrd for Packet {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
@@ -18,7 +51,7 @@ impl Ord for Packet {
             // both lists -> for first non-equal value, left should be smaller; else left should be shorter
             (Value::Array(a), Value::Array(b)) => {
                 std::iter::zip(a, b)
-                    .map(|(a, b)| (Packet(a.clone()).cmp(&Packet(b.clone()))))
+                    .map(|(a, b)| Packet(a.clone()).cmp(&Packet(b.clone())))
                     .find(|x| *x != std::cmp::Ordering::Equal)
                     .unwrap_or_else(|| a.len().cmp(&b.len()))
             },
@@ -34,84 +67,64 @@ impl Ord for Packet {
     }
 }
 
-pub fn day13(input_lines: &str) -> (String, String) {
-    let lines: Vec<&str> = input_lines.lines().collect();
-    let pairs = lines.split(|l| l.is_empty());
-    let mut correctly_ordered_pairs = Vec::new();
-
-    for (i, pair) in pairs.enumerate() {
-        let first = Packet(serde_json::from_str(pair[0]).unwrap());
-        let second = Packet(serde_json::from_str(pair[1]).unwrap());
-
-        if first < second {
-            correctly_ordered_pairs.push(i + 1);
-        }
-    }
-    let answer1: usize = correctly_ordered_pairs.iter().sum();
-
-    let mut part2_lines = lines.clone();
-    let mut dividers = vec!["[[2]]", "[[6]]"];
-    let divider_packets: Vec<Packet> = dividers.iter().map(|p| Packet(serde_json::from_str(p).unwrap())).collect();
-    part2_lines.append(&mut dividers);
-    let lines = part2_lines
-        .iter()
-        .filter(|l| !l.is_empty())
-        .map(|l| Packet(serde_json::from_str(l).unwrap()))
-        .sorted();
-
-    let answer2: usize = lines
-        .enumerate()
-        .filter_map(|(i, x)| {
-            if divider_packets.contains(&x) {
-                Some(i + 1)
-            } else {
-                None
-            }
-        })
-        .product();
-    (format!("{}", answer1), format!("{}", answer2))
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day13;
 
-    const TEST_INPUT: &str = "[1,1,3,1,1]
-[1,1,5,1,1]
+crate::register!(Day13);
 
-[[1],[2,3,4]]
-[[1],4]
+impl Solution for Day13 {
+    // Every packet in the input, in order
+    type Input = Vec<Packet>;
 
-[9]
-[[8,7,6]]
-
-[[4,4],4,4]
-[[4,4],4,4,4]
-
-[7,7,7,7]
-[7,7,7]
+    fn day(&self) -> usize {
+        13
+    }
 
-[]
-[3]
+    fn title(&self) -> &'static str {
+        "Distress Signal"
+    }
 
-[[[]]]
-[[]]
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        input_lines
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| Packet::from_str(l).map_err(|e| e.within(input_lines, l)))
+            .collect()
+    }
 
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
+    fn part1(&self, packets: &Self::Input) -> Result<Answer, SolveError> {
+        if packets.len() % 2 != 0 {
+            return Err(SolveError::new("the last packet doesn't have a partner"));
+        }
 
-    #[test]
-    fn check_day13_part1_case1() {
-        assert_eq!(day13(TEST_INPUT).0, "13".to_string())
-    }
+        let mut correctly_ordered_pairs = Vec::new();
 
-    #[test]
-    fn check_day13_part2_case1() {
-        assert_eq!(day13(TEST_INPUT).1, "140".to_string())
+        for (i, pair) in packets.chunks(2).enumerate() {
+            if pair[0] < pair[1] {
+                correctly_ordered_pairs.push(i + 1);
+            }
+        }
+        let answer1: usize = correctly_ordered_pairs.iter().sum();
+        Ok(answer1.into())
     }
 
-    #[test]
-    fn check_day13_both_case1() {
-        assert_eq!(day13(TEST_INPUT), ("13".to_string(), "140".to_string()))
+    fn part2(&self, packets: &Self::Input) -> Result<Answer, SolveError> {
+        let dividers = ["[[2]]", "[[6]]"];
+        let divider_packets: Vec<Packet> = dividers.iter().map(|p| Packet::from_str(p).unwrap()).collect();
+        let lines = packets
+            .iter()
+            .chain(divider_packets.iter())
+            .sorted();
+
+        let answer2: usize = lines
+            .enumerate()
+            .filter_map(|(i, x)| {
+                if divider_packets.contains(x) {
+                    Some(i + 1)
+                } else {
+                    None
+                }
+            })
+            .product();
+        Ok(answer2.into())
     }
 }
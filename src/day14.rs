@@ -1,20 +1,28 @@
-use std::{str::FromStr, num::ParseIntError, collections::HashSet};
+//! Day 14: Regolith Reservoir.
 
+use std::{str::FromStr, collections::HashSet};
+
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+/// A position in the cave, as `x,y` with `y` increasing downwards.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct Point(i32, i32);
+pub struct Point(pub i32, pub i32);
 
 impl FromStr for Point {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (a, b) = s.split_once(',').unwrap();
-        let x = a.parse()?;
-        let y = b.parse()?;
+        let (a, b) = s
+            .split_once(',')
+            .ok_or_else(|| ParseError::new(s, s, "expected a point like 498,4"))?;
+        let x = parse_field(s, a)?;
+        let y = parse_field(s, b)?;
         Ok(Point(x, y))
     }
 }
 
-fn list_of_points_in_wall(wall: &Vec<Point>) -> Vec<Point> {
+fn list_of_points_in_wall(wall: &[Point]) -> Option<Vec<Point>> {
     let mut wall_points = Vec::new();
     for ix in 0..wall.len() - 1 {
         let mut start = wall[ix].clone();
@@ -22,7 +30,8 @@ fn list_of_points_in_wall(wall: &Vec<Point>) -> Vec<Point> {
         wall_points.push(start.clone());
 
         if start.0 != end.0 && start.1 != end.1 {
-            panic!("diagonal wall!");
+            // diagonal wall!
+            return None;
         }
 
         while start.0 != end.0 {
@@ -35,7 +44,7 @@ fn list_of_points_in_wall(wall: &Vec<Point>) -> Vec<Point> {
             wall_points.push(start.clone());
         }
     }
-    wall_points
+    Some(wall_points)
 }
 
 fn let_sand_fall(sand_source: &Point, obstacles: &HashSet<Point>, bottom: i32, hard_bottom: bool) -> Option<Point> {
@@ -67,64 +76,80 @@ fn let_sand_fall(sand_source: &Point, obstacles: &HashSet<Point>, bottom: i32, h
     Some(curr)
 }
 
-pub fn day14(input_lines: &str) -> (String, String) {
-    let walls: Vec<Vec<Point>> = input_lines.lines().map(|l| {
-        l.split(" -> ").map(|t| Point::from_str(t).unwrap()).collect()
-    }).collect();
-    let sand_source = Point(500, 0);
+pub struct Day14;
 
-    // list of points that are in a wall
-    let wall_points: HashSet<Point> = walls.iter().flat_map(list_of_points_in_wall).collect();
+crate::register!(Day14);
 
-    // where is the bottommost wall?
-    let bottom = wall_points.iter().map(|p| p.1).max().unwrap();
+impl Solution for Day14 {
+    // Every point that is part of a wall
+    type Input = HashSet<Point>;
 
-    // Part 1: no floor
-    let mut obstacles_1 = wall_points.clone().into_iter().collect();
-    while let Some(p) = let_sand_fall(&sand_source, &obstacles_1, bottom, false) {
-        if !obstacles_1.insert(p) { 
-            panic!("double stacking!") 
-        }
+    fn day(&self) -> usize {
+        14
     }
-    let answer1 = obstacles_1.len() - wall_points.len();
 
-    // Part 2: floor is 2 below the lowest wall
-    let new_bottom = bottom + 2;
-    let mut obstacles_2 = wall_points.clone().into_iter().collect();
-
-    loop {
-        match let_sand_fall(&sand_source, &obstacles_2, new_bottom, true) {
-            Some(Point(500, 0)) => { obstacles_2.insert(Point(500, 0)); break; },
-            Some(p) => { 
-                if !obstacles_2.insert(p) { panic!("double stacking!") }
-            },
-            None => panic!("ran out of bottom!"), 
-        }
+    fn title(&self) -> &'static str {
+        "Regolith Reservoir"
     }
 
-    let answer2 = obstacles_2.len() - wall_points.len();
-    (format!("{}", answer1), format!("{}", answer2))
-}
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        let mut wall_points = HashSet::new();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        for l in input_lines.lines() {
+            let wall: Vec<Point> = l
+                .split(" -> ")
+                .map(|t| Point::from_str(t).map_err(|e| e.within(input_lines, t)))
+                .collect::<Result<_, _>>()?;
 
-    const TEST_INPUT: &str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+            // list of points that are in a wall
+            let points = list_of_points_in_wall(&wall)
+                .ok_or_else(|| ParseError::new(input_lines, l, "walls must be horizontal or vertical"))?;
+            wall_points.extend(points);
+        }
 
-    #[test]
-    fn check_day14_part1_case1() {
-        assert_eq!(day14(TEST_INPUT).0, "24".to_string())
+        if wall_points.is_empty() {
+            return Err(ParseError::truncated(input_lines, "there are no walls"));
+        }
+
+        Ok(wall_points)
     }
 
-    #[test]
-    fn check_day14_part2_case1() {
-        assert_eq!(day14(TEST_INPUT).1, "93".to_string())
+    fn part1(&self, wall_points: &Self::Input) -> Result<Answer, SolveError> {
+        let sand_source = Point(500, 0);
+
+        // where is the bottommost wall?
+        let bottom = wall_points.iter().map(|p| p.1).max().unwrap();
+
+        // Part 1: no floor
+        let mut obstacles_1 = wall_points.clone();
+        while let Some(p) = let_sand_fall(&sand_source, &obstacles_1, bottom, false) {
+            if !obstacles_1.insert(p) { 
+                panic!("double stacking!") 
+            }
+        }
+        let answer1 = obstacles_1.len() - wall_points.len();
+        Ok(answer1.into())
     }
 
-    #[test]
-    fn check_day14_both_case1() {
-        assert_eq!(day14(TEST_INPUT), ("24".to_string(), "93".to_string()))
+    fn part2(&self, wall_points: &Self::Input) -> Result<Answer, SolveError> {
+        let sand_source = Point(500, 0);
+        let bottom = wall_points.iter().map(|p| p.1).max().unwrap();
+
+        // Part 2: floor is 2 below the lowest wall
+        let new_bottom = bottom + 2;
+        let mut obstacles_2 = wall_points.clone();
+
+        loop {
+            match let_sand_fall(&sand_source, &obstacles_2, new_bottom, true) {
+                Some(Point(500, 0)) => { obstacles_2.insert(Point(500, 0)); break; },
+                Some(p) => { 
+                    if !obstacles_2.insert(p) { panic!("double stacking!") }
+                },
+                None => panic!("ran out of bottom!"), 
+            }
+        }
+
+        let answer2 = obstacles_2.len() - wall_points.len();
+        Ok(answer2.into())
     }
 }
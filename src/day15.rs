@@ -1,19 +1,42 @@
-use std::{str::FromStr, num::ParseIntError, collections::HashSet};
+//! Day 15: Beacon Exclusion Zone.
+
+use std::{str::FromStr, collections::HashSet};
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::error::{parse_field, parse_lines, ParseError, SolveError};
+use crate::solution::{Answer, Params, Solution};
+
+/// A position in the grid, as `x, y`.
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
-struct Point(i32, i32);
+pub struct Point(pub i32, pub i32);
 
+/// A sensor, and the beacon closest to it.  No other beacon can be as close, so the sensor rules
+/// out every position within that distance.
 #[derive(Debug)]
-struct Sensor {
+pub struct Sensor {
     location: Point,
     closest: Point,
     distance: i32,
 }
 
 impl Sensor {
-    fn is_within_range(&self, point: &Point) -> bool {
+    pub fn location(&self) -> Point {
+        self.location
+    }
+
+    /// The position of the beacon closest to this sensor.
+    pub fn closest_beacon(&self) -> Point {
+        self.closest
+    }
+
+    /// How far the sensor can see: the distance to the closest beacon.
+    pub fn range(&self) -> i32 {
+        self.distance
+    }
+
+    /// Whether `point` is no further away than the closest beacon, so can't hold another beacon.
+    pub fn is_within_range(&self, point: &Point) -> bool {
         let separation = manhattan_distance(&self.location, point);
         separation <= self.distance
     }
@@ -32,23 +55,26 @@ impl Sensor {
     }
 }
 
-fn manhattan_distance(a: &Point, b: &Point) -> i32 {
+/// The distance between two points, moving only along the grid.
+pub fn manhattan_distance(a: &Point, b: &Point) -> i32 {
     i32::abs(a.0 - b.0) + i32::abs(a.1 - b.1)
 }
 
 impl FromStr for Sensor {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"Sensor at x=(\S+), y=(\S+): closest beacon is at x=(\S+), y=(\S+)").unwrap();
+            static ref RE: Regex = Regex::new(r"^Sensor at x=(\S+), y=(\S+): closest beacon is at x=(\S+), y=(\S+)$").unwrap();
         }
 
-        let caps = RE.captures(s).unwrap();
-        let s_x = caps.get(1).unwrap().as_str().parse()?;
-        let s_y = caps.get(2).unwrap().as_str().parse()?;
-        let b_x = caps.get(3).unwrap().as_str().parse()?;
-        let b_y = caps.get(4).unwrap().as_str().parse()?;
+        let caps = RE
+            .captures(s)
+            .ok_or_else(|| ParseError::new(s, s, "expected \"Sensor at x=X, y=Y: closest beacon is at x=X, y=Y\""))?;
+        let s_x = parse_field(s, caps.get(1).unwrap().as_str())?;
+        let s_y = parse_field(s, caps.get(2).unwrap().as_str())?;
+        let b_x = parse_field(s, caps.get(3).unwrap().as_str())?;
+        let b_y = parse_field(s, caps.get(4).unwrap().as_str())?;
 
         let location = Point(s_x, s_y);
         let closest = Point(b_x, b_y);
@@ -61,105 +87,104 @@ impl FromStr for Sensor {
         })
     }
 }
- 
-fn day15_inner(input_lines: &str, target_y: i32) -> (String, String) {
-    let sensors: Vec<Sensor> = input_lines.lines().map(|l| Sensor::from_str(l).unwrap()).collect();
-
-    // Part 1: how many squares where a beacon cannot be present, in row target_y
-    let minimum_x = sensors.iter().map(|s| s.location.0 - s.distance).min().unwrap();
-    let maximum_x = sensors.iter().map(|s| s.location.0 + s.distance).max().unwrap();
-
-    let mut impossible_points_count = 0;
-    for x in minimum_x..=maximum_x {
-        let mut in_range = false;
-        for sensor in &sensors {
-            if sensor.is_within_range(&Point(x, target_y)) {
-                in_range = true;
-                break;
-            }
-        }
-        if in_range {
-            impossible_points_count += 1;
-        }
+
+// Part 1 looks along row `target_y`; part 2 searches the square from 0 to twice that
+fn parse_with_target(input_lines: &str, target_y: i32) -> Result<(Vec<Sensor>, i32), ParseError> {
+    let sensors: Vec<Sensor> = parse_lines(input_lines)?;
+    if sensors.is_empty() {
+        return Err(ParseError::truncated(input_lines, "there are no sensors"));
     }
+    Ok((sensors, target_y))
+}
+
+pub struct Day15;
 
-    // Remove the spots that are actually beacons
-    impossible_points_count -= sensors.iter().map(|s| s.closest).filter(|p| p.1 == target_y).collect::<HashSet<Point>>().len();
+crate::register!(Day15);
 
-    let answer1 = impossible_points_count;
+impl Solution for Day15 {
+    type Input = (Vec<Sensor>, i32);
 
-    // Part 2
-    let max_dimension = target_y * 2;
-    let mut distress_beacon = Point(1,1);
-    let mut sensors_ordered = sensors.iter().collect::<Vec<_>>();
-    sensors_ordered.sort_by(|a, b| a.distance.cmp(&b.distance));
+    fn day(&self) -> usize {
+        15
+    }
 
-    let mut points = HashSet::<Point>::new();
+    fn title(&self) -> &'static str {
+        "Beacon Exclusion Zone"
+    }
 
-    for sensor in sensors_ordered {
-        let circle = sensor.circle_outside();
-        circle.iter().for_each(|p| {points.insert(*p); }); //.filter(|p| p.0 >= 0 && p.0 <= max_dimension && p.1 >= 0 && p.1 <= max_dimension)
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        parse_with_target(input_lines, 2000000)
     }
 
-    for point in points.iter() {
-        if point.0 < 0 || point.0 > max_dimension || point.1 < 0 || point.1 > max_dimension {
-            continue;
+    // The example looks along a different row, given by its `target_y` parameter
+    fn parse_with(&self, input_lines: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        match params.get("target_y") {
+            Some(target_y) => parse_with_target(input_lines, parse_field(target_y, target_y)?),
+            None => self.parse(input_lines),
         }
-        let mut possible = true;
-        for sensor in &sensors {
-            if sensor.is_within_range(point) {
-                possible = false;
-                break;
+    }
+
+    fn part1(&self, (sensors, target_y): &Self::Input) -> Result<Answer, SolveError> {
+        let target_y = *target_y;
+
+        // Part 1: how many squares where a beacon cannot be present, in row target_y
+        let minimum_x = sensors.iter().map(|s| s.location.0 - s.distance).min().unwrap();
+        let maximum_x = sensors.iter().map(|s| s.location.0 + s.distance).max().unwrap();
+
+        let mut impossible_points_count = 0;
+        for x in minimum_x..=maximum_x {
+            let mut in_range = false;
+            for sensor in sensors {
+                if sensor.is_within_range(&Point(x, target_y)) {
+                    in_range = true;
+                    break;
+                }
+            }
+            if in_range {
+                impossible_points_count += 1;
             }
         }
 
-        if possible {
-            distress_beacon = point.clone();
-            break;
-        }
+        // Remove the spots that are actually beacons
+        impossible_points_count -= sensors.iter().map(|s| s.closest).filter(|p| p.1 == target_y).collect::<HashSet<Point>>().len();
+
+        let answer1 = impossible_points_count;
+        Ok(answer1.into())
     }
-    
-    let answer2 = distress_beacon.0 as i64 * 4_000_000_i64 + distress_beacon.1 as i64;
 
-    (format!("{}", answer1), format!("{}", answer2))
-}
+    fn part2(&self, (sensors, target_y): &Self::Input) -> Result<Answer, SolveError> {
+        // Part 2
+        let max_dimension = target_y * 2;
+        let mut distress_beacon = Point(1,1);
+        let mut sensors_ordered = sensors.iter().collect::<Vec<_>>();
+        sensors_ordered.sort_by_key(|s| s.distance);
 
-pub fn day15(input_lines: &str) -> (String, String) {
-    let (answer1, answer2) = day15_inner(input_lines, 2000000);
-    (answer1, answer2)
-}
+        let mut points = HashSet::<Point>::new();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3";
-
-    #[test]
-    fn check_day15_part1_case1() {
-        assert_eq!(day15_inner(TEST_INPUT, 10).0, "26".to_string())
-    }
+        for sensor in sensors_ordered {
+            let circle = sensor.circle_outside();
+            circle.iter().for_each(|p| {points.insert(*p); }); //.filter(|p| p.0 >= 0 && p.0 <= max_dimension && p.1 >= 0 && p.1 <= max_dimension)
+        }
 
-    #[test]
-    fn check_day15_part2_case1() {
-        assert_eq!(day15_inner(TEST_INPUT, 10).1, "56000011".to_string())
-    }
+        for point in points.iter() {
+            if point.0 < 0 || point.0 > max_dimension || point.1 < 0 || point.1 > max_dimension {
+                continue;
+            }
+            let mut possible = true;
+            for sensor in sensors {
+                if sensor.is_within_range(point) {
+                    possible = false;
+                    break;
+                }
+            }
 
-    #[test]
-    fn check_day15_both_case1() {
-        assert_eq!(day15_inner(TEST_INPUT, 10), ("26".to_string(), "56000011".to_string()))
+            if possible {
+                distress_beacon = *point;
+                break;
+            }
+        }
+        
+        let answer2 = distress_beacon.0 as i64 * 4_000_000_i64 + distress_beacon.1 as i64;
+        Ok(answer2.into())
     }
 }
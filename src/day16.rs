@@ -1,10 +1,16 @@
+//! Day 16: Proboscidea Volcanium.
+
 use std::collections::{HashMap, VecDeque};
 
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::error::{parse_field, ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+/// A valve, with its flow rate and the valves its tunnels lead to.
 #[derive(Clone)]
-struct Valve {
+pub struct Valve {
     flow_rate: i32,
     neighbours: Vec<String>,
 }
@@ -42,7 +48,7 @@ fn bfs(valves: &ValveSet, start: &str, end: &str) -> i32 {
 }
 
 // inspired by https://gist.github.com/liampwll/351fb848f05e8efd257ac87c7d09d1b0
-fn recurse(valves: &ValveSet, paths: &PathSetMap, opened: &Vec<String>, from: &str, total_released: i32, time_left: i32, best_total: i32) -> i32 {
+fn recurse(valves: &ValveSet, paths: &PathSetMap, opened: &[String], from: &str, total_released: i32, time_left: i32, best_total: i32) -> i32 {
     let valve = valves.get(from).unwrap();
     let mut new_best = best_total;
 
@@ -55,7 +61,7 @@ fn recurse(valves: &ValveSet, paths: &PathSetMap, opened: &Vec<String>, from: &s
     }
 
     if !opened.contains(&from.to_owned()) {
-        let mut new_opened = opened.clone();
+        let mut new_opened = opened.to_vec();
         new_opened.push(from.to_owned());
         let new_total = total_released + (valve.flow_rate * time_left);
         new_best = recurse(valves, paths, &new_opened, from, new_total, time_left - 1, new_best);
@@ -65,10 +71,11 @@ fn recurse(valves: &ValveSet, paths: &PathSetMap, opened: &Vec<String>, from: &s
         }
     }
 
-    return new_best;
+    new_best
 }
 
-fn recurse_with_elephant(valves: &ValveSet, paths: &PathSetMap, opened: &Vec<String>, from: &str, total_released: i32, time_left: i32, best_total: i32, elephant: bool) -> i32 {
+#[allow(clippy::too_many_arguments)]
+fn recurse_with_elephant(valves: &ValveSet, paths: &PathSetMap, opened: &[String], from: &str, total_released: i32, time_left: i32, best_total: i32, elephant: bool) -> i32 {
     let valve = valves.get(from).unwrap();
     let mut new_best = best_total;
 
@@ -81,7 +88,7 @@ fn recurse_with_elephant(valves: &ValveSet, paths: &PathSetMap, opened: &Vec<Str
     }
 
     if !opened.contains(&from.to_owned()) {
-        let mut new_opened = opened.clone();
+        let mut new_opened = opened.to_vec();
         new_opened.push(from.to_owned());
         let new_total = total_released + (valve.flow_rate * time_left);
         new_best = recurse_with_elephant(valves, paths, &new_opened, from, new_total, time_left - 1, new_best, elephant);
@@ -94,72 +101,83 @@ fn recurse_with_elephant(valves: &ValveSet, paths: &PathSetMap, opened: &Vec<Str
         }
     }
 
-    return new_best;
+    new_best
 }
 
-pub fn day16(input_lines: &str) -> (String, String) {
-    let valves: ValveSet = input_lines.lines().map(|s| {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"Valve (.*) has flow rate=(\d+); tunnel(?:s?) lead(?:s?) to valve(?:s?) (.*)").unwrap();
-        }
-        
-        let caps = RE.captures(s).unwrap();
-        let name = caps.get(1).unwrap().as_str().to_owned();
-        let flow_rate = caps.get(2).unwrap().as_str().parse().unwrap();
-        let neighbours = caps.get(3).unwrap().as_str().split(", ").map(|s| s.to_owned()).collect();
-
-        (name, Valve { flow_rate, neighbours })
-    }).collect();
-    println!("Parsed input");
-
-    let mut keys: Vec<String> = valves.keys().filter(|k| valves.get(*k).unwrap().flow_rate != 0).map(|s| s.to_owned()).collect();
-    keys.push("AA".to_string());
-
-    let mut paths: HashMap<String, PathSet> = HashMap::new();
-    for k in &keys {
-        paths.insert(k.to_string(), PathSet::new());
-        for k2 in &keys {
-            if k2 != k {
-                paths.get_mut(k).unwrap().insert(k2.to_string(), bfs(&valves, k, k2));
+pub struct Day16;
+
+crate::register!(Day16);
+
+impl Solution for Day16 {
+    // The valves, and the distances between every pair of valves worth visiting
+    type Input = (ValveSet, PathSetMap);
+
+    fn day(&self) -> usize {
+        16
+    }
+
+    fn title(&self) -> &'static str {
+        "Proboscidea Volcanium"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        let parsed = input_lines.lines().map(|s| {
+            lazy_static! {
+                static ref RE: Regex = Regex::new(r"Valve (.*) has flow rate=(\d+); tunnel(?:s?) lead(?:s?) to valve(?:s?) (.*)").unwrap();
+            }
+            
+            let caps = RE
+                .captures(s)
+                .ok_or_else(|| ParseError::new(input_lines, s, "expected \"Valve XX has flow rate=N; tunnels lead to valves YY, ZZ\""))?;
+            let name = caps.get(1).unwrap().as_str();
+            let flow_rate = parse_field(input_lines, caps.get(2).unwrap().as_str())?;
+            let neighbours: Vec<&str> = caps.get(3).unwrap().as_str().split(", ").collect();
+
+            Ok((name, flow_rate, neighbours))
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        // Every tunnel must lead to a valve that exists
+        for (_, _, neighbours) in &parsed {
+            if let Some(missing) = neighbours.iter().find(|n| !parsed.iter().any(|(name, _, _)| name == *n)) {
+                return Err(ParseError::new(input_lines, missing, "no such valve"));
             }
         }
-    }
-    println!("Worked out paths");
 
-    let answer1 = recurse(&valves, &paths, vec!["AA".to_owned()].as_ref(), "AA", 0, 29, 0);
-    println!("Solved part 1");
+        let valves: ValveSet = parsed.into_iter().map(|(name, flow_rate, neighbours)| {
+            let neighbours = neighbours.iter().map(|s| s.to_string()).collect();
+            (name.to_owned(), Valve { flow_rate, neighbours })
+        }).collect();
+        log::info!("Parsed input");
 
-    let answer2 = recurse_with_elephant(&valves, &paths, vec!["AA".to_owned()].as_ref(), "AA", 0, 25, 0, false);
-    (format!("{}", answer1), format!("{}", answer2))
-}
+        if !valves.contains_key("AA") {
+            return Err(ParseError::truncated(input_lines, "there is no valve AA to start from"));
+        }
+
+        let mut keys: Vec<String> = valves.keys().filter(|k| valves.get(*k).unwrap().flow_rate != 0).map(|s| s.to_owned()).collect();
+        keys.push("AA".to_string());
+
+        let mut paths: HashMap<String, PathSet> = HashMap::new();
+        for k in &keys {
+            paths.insert(k.to_string(), PathSet::new());
+            for k2 in &keys {
+                if k2 != k {
+                    paths.get_mut(k).unwrap().insert(k2.to_string(), bfs(&valves, k, k2));
+                }
+            }
+        }
+        log::info!("Worked out paths");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II";
-
-    #[test]
-    fn check_day16_part1_case1() {
-        assert_eq!(day16(TEST_INPUT).0, "1651".to_string())
+        Ok((valves, paths))
     }
 
-    #[test]
-    fn check_day16_part2_case1() {
-        assert_eq!(day16(TEST_INPUT).1, "1707".to_string())
+    fn part1(&self, (valves, paths): &Self::Input) -> Result<Answer, SolveError> {
+        let answer1 = recurse(valves, paths, &["AA".to_owned()], "AA", 0, 29, 0);
+        log::info!("Solved part 1");
+        Ok(answer1.into())
     }
 
-    #[test]
-    fn check_day16_both_case1() {
-        assert_eq!(day16(TEST_INPUT), ("1651".to_string(), "1707".to_string()))
+    fn part2(&self, (valves, paths): &Self::Input) -> Result<Answer, SolveError> {
+        let answer2 = recurse_with_elephant(valves, paths, &["AA".to_owned()], "AA", 0, 25, 0, false);
+        Ok(answer2.into())
     }
 }
@@ -1,5 +1,10 @@
+//! Day 17: Pyroclastic Flow.
+
 use std::collections::HashSet;
 
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
 enum Shape {
     HLine,
     Plus,
@@ -18,8 +23,9 @@ const SHAPES: [Shape; 5] = [
 
 type Point = (i32, i32);
 
+/// Which way a jet of hot gas pushes the falling rock.
 #[derive(Copy, Clone)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
@@ -102,17 +108,49 @@ fn draw(board: &HashSet<Point>) {
                 row = format!("{}{}", row, " ");
             }
         }
-        println!("{}", row);
+        log::trace!("{}", row);
     }
 }
 
-pub fn day17(input_lines: &str) -> (String, String) {
-    let moves: Vec<Direction> = input_lines.chars().map(|c| match c {
-        '<' => Direction::Left,
-        '>' => Direction::Right,
-        _ => panic!("invalid move!"),
-    }).collect();
+pub struct Day17;
+
+crate::register!(Day17);
+
+impl Solution for Day17 {
+    // The pattern of jets of hot gas
+    type Input = Vec<Direction>;
+
+    fn day(&self) -> usize {
+        17
+    }
+
+    fn title(&self) -> &'static str {
+        "Pyroclastic Flow"
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        input_lines.char_indices().map(|(ix, c)| match c {
+            '<' => Ok(Direction::Left),
+            '>' => Ok(Direction::Right),
+            _ => Err(ParseError::new(input_lines, &input_lines[ix..ix + c.len_utf8()], "invalid move! (expected < or >)")),
+        }).collect()
+    }
+
+    fn part1(&self, moves: &Self::Input) -> Result<Answer, SolveError> {
+        if moves.is_empty() {
+            return Err(SolveError::new("there are no jets of gas"));
+        }
+        let answer1 = drop_rocks(moves, 2022);
+        Ok(answer1.into())
+    }
+
+    fn part2(&self, _moves: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+}
 
+// Drop `count` rocks, pushed about by the jets in `moves`, and return the height of the tower
+fn drop_rocks(moves: &[Direction], count: usize) -> i32 {
     let mut board = HashSet::<Point>::new();
     for x in 0..=MAX_X {
         board.insert((x, 0));
@@ -120,7 +158,7 @@ pub fn day17(input_lines: &str) -> (String, String) {
     let mut top = 0;
     let mut move_counter = 0;
 
-    for ix in 0..2022 {
+    for ix in 0..count {
         let mut rock = get_rock(&SHAPES[ix % SHAPES.len()], top + 4);
 
         loop {
@@ -153,33 +191,23 @@ pub fn day17(input_lines: &str) -> (String, String) {
             }
 
         }
-        // println!("done rock {}", ix);
-        // draw(&board);
+        log::trace!("done rock {}", ix);
+        if log::log_enabled!(log::Level::Trace) {
+            draw(&board);
+        }
     }
 
-    let answer1 = top;
-    let answer2 = 0;
-    (format!("{}", answer1), format!("{}", answer2))
+    top
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const TEST_INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-
-    #[test]
-    fn check_day17_part1_case1() {
-        assert_eq!(day17(TEST_INPUT).0, "3068".to_string())
-    }
-
-    #[test]
-    fn check_day17_part2_case1() {
-        assert_eq!(day17("").1, "0".to_string())
-    }
+    use crate::solution::Solver;
 
     #[test]
-    fn check_day17_both_case1() {
-        assert_eq!(day17(""), ("0".to_string(), "0".to_string()))
+    fn check_day17_bad_move() {
+        let err = Day17.parse_any(">><>\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 5: invalid move! (expected < or >): \"\\n\"");
     }
 }
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A problem found while parsing puzzle input.
+///
+/// The error records where the offending text starts as a byte offset into the string that was
+/// being parsed.  Parsers for a single line or block return errors relative to that line or block,
+/// and the caller uses `within` to make them relative to the whole input, so that the runner can
+/// finally report a line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    offset: usize,
+    text: String,
+    message: String,
+}
+
+impl ParseError {
+    /// An error about `bad`, which should be a slice of `s` (the string being parsed).
+    pub fn new(s: &str, bad: &str, message: impl Into<String>) -> Self {
+        ParseError {
+            offset: offset_of(s, bad).or_else(|| s.find(bad)).unwrap_or(0),
+            text: bad.to_owned(),
+            message: message.into(),
+        }
+    }
+
+    /// An error for input that stops before something expected turns up.
+    pub fn truncated(s: &str, message: impl Into<String>) -> Self {
+        ParseError::new(s, &s[s.len()..], message)
+    }
+
+    /// Make the error relative to `outer`, given that it was found while parsing `s` (a slice of
+    /// `outer`).
+    pub fn within(mut self, outer: &str, s: &str) -> Self {
+        self.offset += offset_of(outer, s).unwrap_or(0);
+        self
+    }
+}
+
+/// Where `inner` starts within `outer`, if it is a slice of it.
+fn offset_of(outer: &str, inner: &str) -> Option<usize> {
+    let start = outer.as_ptr() as usize;
+    let ptr = inner.as_ptr() as usize;
+    (ptr >= start && ptr + inner.len() <= start + outer.len()).then(|| ptr - start)
+}
+
+/// Parse `field` (a slice of `s`) with its `FromStr` implementation.
+pub fn parse_field<T>(s: &str, field: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    field
+        .trim()
+        .parse()
+        .map_err(|e| ParseError::new(s, field, format!("expected {} ({})", std::any::type_name::<T>(), e)))
+}
+
+/// Parse every line of `input` with `T`'s `FromStr` implementation.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr<Err = ParseError>,
+{
+    input
+        .lines()
+        .map(|line| T::from_str(line).map_err(|e| e.within(input, line)))
+        .collect()
+}
+
+/// A position in the puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// Line number, counting from 1.
+    pub line: usize,
+    /// Column (in characters), counting from 1.
+    pub column: usize,
+    /// The offending text, which is empty if the input stopped short.
+    pub text: String,
+}
+
+/// Why a solution couldn't produce an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    message: String,
+    location: Option<Location>,
+}
+
+impl SolveError {
+    pub fn new(message: impl Into<String>) -> Self {
+        SolveError {
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    /// Turn a `ParseError` (relative to the whole of `input`) into a located error.
+    pub fn parse(input: &str, error: ParseError) -> Self {
+        let offset = error.offset.min(input.len());
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        SolveError {
+            message: error.message,
+            location: Some(Location {
+                line: before.matches('\n').count() + 1,
+                column: before[line_start..].chars().count() + 1,
+                text: error.text,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.location {
+            None => write!(f, "{}", self.message),
+            Some(loc) if loc.text.is_empty() => write!(
+                f,
+                "line {}, column {}: {} (input ended early)",
+                loc.line, loc.column, self.message
+            ),
+            Some(loc) => write!(
+                f,
+                "line {}, column {}: {}: {:?}",
+                loc.line, loc.column, self.message, loc.text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_error_location() {
+        let input = "1, 2\n3, x4\n";
+        let bad = &input[8..10];
+        let err = SolveError::parse(input, ParseError::new(input, bad, "not a number"));
+        assert_eq!(err.to_string(), "line 2, column 4: not a number: \"x4\"");
+    }
+
+    #[test]
+    fn check_error_within_line() {
+        let input = "R 4\nU ?";
+        let line = input.lines().nth(1).unwrap();
+        let err = parse_field::<u8>(line, &line[2..]).unwrap_err().within(input, line);
+        let loc = SolveError::parse(input, err).location.unwrap();
+        assert_eq!((loc.line, loc.column, loc.text.as_str()), (2, 3, "?"));
+    }
+
+    #[test]
+    fn check_error_truncated() {
+        let input = "abc\n";
+        let err = SolveError::parse(input, ParseError::truncated(input, "missing steps"));
+        assert_eq!(err.to_string(), "line 2, column 1: missing steps (input ended early)");
+    }
+}
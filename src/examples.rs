@@ -0,0 +1,31 @@
+// The example tests: build.rs generates a test for each part of every input that has an
+// `.expected` file beside it, each of which calls `check`.
+
+use crate::answers::Expected;
+use crate::helpers::load_named_input;
+use crate::registry;
+
+// Run one part of `day` on its input called `name`, with each variant of the day's solution, and
+// compare the answer with the one in the `.expected` file
+fn check(day: usize, name: &str, part: usize) {
+    let variants = registry::variants(day);
+    assert!(!variants.is_empty(), "no solution for this day");
+    let input = load_named_input(day, name).unwrap();
+    let expected = Expected::load(day, name).unwrap().expect("no .expected file");
+
+    for variant in variants {
+        let solution = variant.solver;
+        let parsed = solution
+            .parse_any_with(&input, &expected.params)
+            .unwrap_or_else(|e| panic!("{}: {}", variant.name, e));
+        let answer = match part {
+            1 => solution.part1_any(parsed.as_ref()),
+            _ => solution.part2_any(parsed.as_ref()),
+        }
+        .unwrap_or_else(|e| panic!("{}: {}", variant.name, e));
+
+        assert_eq!(&answer, expected.answers.get(day, part).unwrap(), "variant {}", variant.name);
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
@@ -1,4 +1,190 @@
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The name of each day's real puzzle input, among its named inputs.
+pub const REAL_INPUT: &str = "real";
+
+/// The environment variable that, if set, gives the directory to read inputs from instead of the
+/// repository's `inputs` directory.
+pub const INPUTS_VAR: &str = "AOC_INPUTS";
+
+/// The directory holding every day's inputs: `$AOC_INPUTS` if that's set, or else the `inputs`
+/// directory in the repository (wherever the program is run from).
+pub fn inputs_root() -> PathBuf {
+    match env::var_os(INPUTS_VAR) {
+        Some(root) if !root.is_empty() => PathBuf::from(root),
+        _ => Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"),
+    }
+}
+
+/// Every file in `inputs`, as its path relative to that directory (like `08/real.txt`) and its
+/// contents, built into the program by the `embed-inputs` feature.
+#[cfg(feature = "embed-inputs")]
+static EMBEDDED_INPUTS: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+/// The embedded copy of the file at `relative` in `inputs`, if there is one and `$AOC_INPUTS`
+/// hasn't asked for inputs from elsewhere.
+#[cfg(feature = "embed-inputs")]
+fn embedded_input_file(relative: &str) -> Option<&'static str> {
+    match env::var_os(INPUTS_VAR) {
+        Some(root) if !root.is_empty() => None,
+        _ => EMBEDDED_INPUTS.iter().find(|(path, _)| *path == relative).map(|(_, contents)| *contents),
+    }
+}
+
+#[cfg(not(feature = "embed-inputs"))]
+fn embedded_input_file(_relative: &str) -> Option<&'static str> {
+    None
+}
+
+/// Read the file at `relative` (like `08/real.txt`) in the inputs directory.  With the
+/// `embed-inputs` feature, the copy built into the program is used if there is one, so that it runs
+/// without the inputs directory; otherwise (or if `$AOC_INPUTS` is set) it's read from
+/// `inputs_root()`.
+pub fn read_input_file(relative: &str) -> io::Result<String> {
+    match embedded_input_file(relative) {
+        Some(contents) => Ok(contents.to_owned()),
+        None => std::fs::read_to_string(inputs_root().join(relative)),
+    }
+}
+
+/// The directory holding `day`'s inputs, e.g. `inputs/08`.
+pub fn input_dir(day: usize) -> PathBuf {
+    inputs_root().join(format!("{:02}", day))
+}
+
+/// Load `day`'s real input, panicking if it can't be read.
 pub fn load_input(day: usize) -> String {
-    std::fs::read_to_string(format!("{}/{}", "inputs", day))
-        .expect("Can't open/read input file")
-}
\ No newline at end of file
+    load_named_input(day, REAL_INPUT).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Load the input called `name` for `day`, from `inputs/<day>/<name>.txt` (see `read_input_file`),
+/// normalised with `normalize_input`.
+pub fn load_named_input(day: usize, name: &str) -> Result<String, String> {
+    let relative = format!("{:02}/{}.txt", day, name);
+    let path = inputs_root().join(&relative);
+    match read_input_file(&relative) {
+        Ok(input) => Ok(normalize_input(&input)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(format!(
+            "there's no input `{}` for day {}: {} doesn't exist (set {} to read inputs from elsewhere)",
+            name,
+            day,
+            path.display(),
+            INPUTS_VAR
+        )),
+        Err(e) => Err(format!("can't read input `{}` for day {} from {} ({})", name, day, path.display(), e)),
+    }
+}
+
+/// Tidy up puzzle input however it was saved, so that solutions only ever see one form: any byte
+/// order mark is dropped, Windows line endings become `\n`, trailing whitespace is removed from
+/// each line, and blank lines at the end (including the final newline) are removed.  Leading
+/// whitespace is kept, since some pictures (like day 5's stacks) need it.
+pub fn normalize_input(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let lines: Vec<&str> = input.lines().map(str::trim_end).collect();
+    lines.join("\n").trim_end().to_owned()
+}
+
+/// The names of `day`'s inputs: `real` first, then the rest in order (with `example2` before
+/// `example10`).
+pub fn input_names(day: usize) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(input_dir(day))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            match path.extension() {
+                Some(extension) if extension == "txt" => Some(path.file_stem()?.to_str()?.to_owned()),
+                _ => None,
+            }
+        })
+        .collect();
+    #[cfg(feature = "embed-inputs")]
+    {
+        let prefix = format!("{:02}/", day);
+        for (path, _) in EMBEDDED_INPUTS {
+            let name = path.strip_prefix(&prefix).and_then(|file| file.strip_suffix(".txt"));
+            if let Some(name) = name.filter(|name| !names.iter().any(|n| n == name)) {
+                if embedded_input_file(path).is_some() {
+                    names.push(name.to_owned());
+                }
+            }
+        }
+    }
+    names.sort_by_cached_key(|name| {
+        let stem = name.trim_end_matches(|c: char| c.is_ascii_digit());
+        let number: u32 = name[stem.len()..].parse().unwrap_or(0);
+        (name != REAL_INPUT, stem.to_owned(), number)
+    });
+    names
+}
+
+/// Split `input` into the blocks of lines separated by blank lines.  Each block is a slice of
+/// `input`, so parse errors within a block can still be located in the whole input.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|b| b.trim_matches('\n'))
+        .filter(|b| !b.is_empty())
+}
+
+/// Format a duration in milliseconds, to the nearest microsecond.
+pub fn format_duration(elapsed: Duration) -> String {
+    let elapsed = elapsed.as_micros();
+    format!("{}.{:03}ms", elapsed / 1000, elapsed % 1000)
+}
+
+/// A short fingerprint of a puzzle input (64-bit FNV-1a, in hex), so that results from different
+/// inputs can be told apart without publishing the inputs themselves.
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_input_names() {
+        assert_eq!(input_names(6), ["real", "example1", "example2", "example3", "example4", "example5"]);
+        assert_eq!(input_names(26), Vec::<String>::new());
+    }
+
+    #[test]
+    fn check_input_normalized() {
+        assert_eq!(normalize_input("\u{feff}1, 2\r\n3, 4\r\n\r\n"), "1, 2\n3, 4");
+        assert_eq!(normalize_input(">><\n"), ">><");
+        // Leading whitespace can matter (as in day 5's crate diagram), so it stays
+        assert_eq!(normalize_input("    [D]\n[N] [C]\n"), "    [D]\n[N] [C]");
+        assert_eq!(normalize_input("    [D]    \n[N] [C]    \r\n\n"), "    [D]\n[N] [C]");
+    }
+
+    #[test]
+    fn check_input_missing() {
+        let e = load_named_input(8, "nonexistent").unwrap_err();
+        assert!(e.starts_with("there's no input `nonexistent` for day 8: "), "{}", e);
+        assert!(e.contains(&Path::new("inputs").join("08").join("nonexistent.txt").display().to_string()), "{}", e);
+        assert!(input_dir(8).is_absolute() || env::var_os(INPUTS_VAR).is_some());
+    }
+
+    #[cfg(feature = "embed-inputs")]
+    #[test]
+    fn check_input_embedded() {
+        assert!(EMBEDDED_INPUTS.iter().any(|(path, _)| *path == "answers.json"));
+        let (_, example) = EMBEDDED_INPUTS.iter().find(|(path, _)| *path == "06/example1.txt").unwrap();
+        assert_eq!(read_input_file("06/example1.txt").unwrap(), *example);
+    }
+
+    #[test]
+    fn check_input_hash() {
+        // Published FNV-1a test vectors
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    }
+}
@@ -0,0 +1,432 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use aoc2022_rs_jtgs::helpers::format_duration;
+
+use crate::bench::{DayBench, Stats};
+
+/// Where bench results are kept: `bench-history.jsonl` in the repository (outside `inputs`, which
+/// the build watches), with one JSON object per line for each step of each day benched, oldest
+/// first.  It's this machine's own, so it isn't checked in.
+pub fn history_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("bench-history.jsonl")
+}
+
+/// The environment variable naming this machine in the history, if its host name won't do (or
+/// can't be found).
+pub const MACHINE_VAR: &str = "AOC_MACHINE";
+
+/// How much slower a step may get before `perf-diff` calls it a regression, in percent.
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// How many of the most recent commits `perf-diff` shows the trend over.
+const TREND_LENGTH: usize = 10;
+
+/// The timings of one step of one day, benched once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// When, in seconds since the Unix epoch.
+    pub time: u64,
+    /// The commit the code was built from, ending `-dirty` if the code had been changed since.
+    pub commit: String,
+    pub machine: String,
+    pub day: usize,
+    /// The step: `parse`, `part 1` or `part 2`.
+    pub part: String,
+    pub variant: String,
+    pub input: String,
+    pub stats: Stats,
+}
+
+impl Record {
+    pub fn to_json(&self) -> Value {
+        let ns = |duration: Duration| duration.as_nanos() as u64;
+        json!({
+            "time": self.time,
+            "commit": self.commit,
+            "machine": self.machine,
+            "day": self.day,
+            "part": self.part,
+            "variant": self.variant,
+            "input": self.input,
+            "runs": self.stats.runs,
+            "min_ns": ns(self.stats.min),
+            "median_ns": ns(self.stats.median),
+            "mean_ns": ns(self.stats.mean),
+            "p95_ns": ns(self.stats.p95),
+            "std_dev_ns": ns(self.stats.std_dev),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Option<Record> {
+        let string = |key: &str| value[key].as_str().map(str::to_owned);
+        let ns = |key: &str| value[key].as_u64().map(Duration::from_nanos);
+        Some(Record {
+            time: value["time"].as_u64()?,
+            commit: string("commit")?,
+            machine: string("machine")?,
+            day: value["day"].as_u64()? as usize,
+            part: string("part")?,
+            variant: string("variant")?,
+            input: string("input")?,
+            stats: Stats {
+                runs: value["runs"].as_u64()? as usize,
+                min: ns("min_ns")?,
+                median: ns("median_ns")?,
+                mean: ns("mean_ns")?,
+                p95: ns("p95_ns")?,
+                std_dev: ns("std_dev_ns")?,
+            },
+        })
+    }
+
+    // What this is a timing of, so that timings of the same thing at different commits can be
+    // compared
+    fn key(&self) -> (usize, &str, &str, &str) {
+        (self.day, &self.part, &self.variant, &self.input)
+    }
+}
+
+/// Where and when timings were made, to go in each record.
+pub struct Stamp {
+    pub time: u64,
+    pub commit: String,
+    pub machine: String,
+}
+
+impl Stamp {
+    /// Now, on this machine, with the code in the repository at `root`.
+    pub fn now(root: &Path) -> Stamp {
+        Stamp {
+            time: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs()),
+            commit: current_commit(root),
+            machine: machine_name(),
+        }
+    }
+
+    /// A record for each step of `bench`, which timed `variant` on the input called `input`.
+    pub fn records(&self, bench: &DayBench, variant: &str, input: &str) -> Vec<Record> {
+        bench
+            .steps
+            .iter()
+            .map(|(part, stats)| Record {
+                time: self.time,
+                commit: self.commit.clone(),
+                machine: self.machine.clone(),
+                day: bench.day,
+                part: part.to_string(),
+                variant: variant.to_owned(),
+                input: input.to_owned(),
+                stats: *stats,
+            })
+            .collect()
+    }
+}
+
+/// The commit checked out in the repository at `root`, ending `-dirty` if the code (rather than,
+/// say, the inputs) has been changed since, or `unknown` if git can't say.
+pub fn current_commit(root: &Path) -> String {
+    let git = |args: &[&str]| Command::new("git").arg("-C").arg(root).args(args).output().ok();
+    let commit = match git(&["rev-parse", "--short=12", "HEAD"]) {
+        Some(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_owned(),
+        _ => return "unknown".to_owned(),
+    };
+    match git(&["diff", "--quiet", "HEAD", "--", "src", "build.rs", "Cargo.toml"]) {
+        Some(output) if output.status.code() == Some(1) => format!("{}-dirty", commit),
+        _ => commit,
+    }
+}
+
+/// The name of this machine: `$AOC_MACHINE`, or else its host name.  If neither can be found, it's
+/// `unknown`, with a warning, since every such machine's timings would be mixed up together.
+pub fn machine_name() -> String {
+    // `hostname` is the only one of these that works on macOS and Windows too
+    let hostname = || {
+        let output = Command::new("hostname").output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let names = [env::var(MACHINE_VAR).ok(), env::var("HOSTNAME").ok(), fs::read_to_string("/etc/hostname").ok()];
+    names
+        .into_iter()
+        .flatten()
+        .chain(iter::once_with(hostname).flatten())
+        .map(|name| name.trim().to_owned())
+        .find(|name| !name.is_empty())
+        .unwrap_or_else(|| {
+            eprintln!("warning: can't tell this machine's name, so calling it `unknown`; set ${} to name it",
+                MACHINE_VAR);
+            "unknown".to_owned()
+        })
+}
+
+/// Add `records` to the end of the history.
+pub fn append(records: &[Record]) -> Result<(), String> {
+    let path = history_path();
+    let mut text = String::new();
+    for record in records {
+        text.push_str(&format!("{}\n", record.to_json()));
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .map_err(|e| format!("can't write {} ({})", path.display(), e))
+}
+
+/// Load the history, which is empty if nothing has been benched yet.
+pub fn load() -> Result<Vec<Record>, String> {
+    let path = history_path();
+    match fs::read_to_string(&path) {
+        Ok(text) => parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("can't read {} ({})", path.display(), e)),
+    }
+}
+
+pub fn parse(text: &str) -> Result<Vec<Record>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .ok()
+                .and_then(|json| Record::from_json(&json))
+                .ok_or_else(|| format!("line {}: expected a bench record", i + 1))
+        })
+        .collect()
+}
+
+/// Each commit in `records`, in the order they were first benched.
+pub fn commits(records: &[Record]) -> Vec<&str> {
+    let mut commits: Vec<&str> = Vec::new();
+    for record in records {
+        if !commits.contains(&record.commit.as_str()) {
+            commits.push(&record.commit);
+        }
+    }
+    commits
+}
+
+/// The commit in `records` that `name` is the start of, or why there isn't exactly one.
+pub fn find_commit<'a>(records: &'a [Record], name: &str) -> Result<&'a str, String> {
+    let matches: Vec<&str> = commits(records).into_iter().filter(|commit| commit.starts_with(name)).collect();
+    match matches[..] {
+        [commit] => Ok(commit),
+        [] => Err(format!("nothing was benched at a commit starting `{}`", name)),
+        _ => Err(format!("`{}` could be any of {}", name, matches.join(", "))),
+    }
+}
+
+/// How the median time of one step changed between two commits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub day: usize,
+    pub part: String,
+    pub variant: String,
+    pub input: String,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// The median at each commit the step was benched at, oldest first, up to and including the
+    /// current one.
+    pub trend: Vec<Duration>,
+}
+
+impl Comparison {
+    /// How much slower the current median is than the baseline, in percent (negative if faster).
+    pub fn change(&self) -> f64 {
+        (self.current.as_secs_f64() / self.baseline.as_secs_f64().max(1e-9) - 1.0) * 100.0
+    }
+}
+
+/// Compare the median times at the `current` commit with those at `baseline`, for each step that
+/// was benched at both.  If a step was benched more than once at a commit, the last time counts.
+pub fn compare(records: &[Record], baseline: &str, current: &str) -> Vec<Comparison> {
+    let commits = commits(records);
+    let last = |key, commit: &str| records.iter().rev().find(|r| r.key() == key && r.commit == commit);
+
+    // In the order they were benched in
+    let mut keys = Vec::new();
+    for record in records.iter().filter(|r| r.commit == current) {
+        if !keys.contains(&record.key()) {
+            keys.push(record.key());
+        }
+    }
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let (baseline, current_record) = (last(key, baseline)?, last(key, current)?);
+            let until_current = commits.iter().position(|commit| *commit == current).map_or(0, |ix| ix + 1);
+            let mut trend: Vec<Duration> = commits[..until_current]
+                .iter()
+                .filter_map(|commit| last(key, commit))
+                .map(|record| record.stats.median)
+                .collect();
+            trend.drain(..trend.len().saturating_sub(TREND_LENGTH));
+            Some(Comparison {
+                day: key.0,
+                part: key.1.to_owned(),
+                variant: key.2.to_owned(),
+                input: key.3.to_owned(),
+                baseline: baseline.stats.median,
+                current: current_record.stats.median,
+                trend,
+            })
+        })
+        .collect()
+}
+
+/// The time `variant` of `day` took on the input called `input` at each commit it was benched at,
+/// oldest first, up to the last `limit` of them: the sum of the median times of its steps.
+pub fn day_trend(records: &[Record], day: usize, variant: &str, input: &str, limit: usize) -> Vec<Duration> {
+    let timed: Vec<&Record> = records
+        .iter()
+        .filter(|r| (r.day, r.variant.as_str(), r.input.as_str()) == (day, variant, input))
+        .collect();
+    let mut trend: Vec<Duration> = commits(records)
+        .into_iter()
+        .filter_map(|commit| {
+            // The last timing of each step at the commit counts
+            let mut steps: Vec<(&str, Duration)> = Vec::new();
+            for record in timed.iter().filter(|record| record.commit == commit) {
+                steps.retain(|(part, _)| *part != record.part);
+                steps.push((&record.part, record.stats.median));
+            }
+            (!steps.is_empty()).then(|| steps.iter().map(|(_, median)| *median).sum())
+        })
+        .collect();
+    trend.drain(..trend.len().saturating_sub(limit));
+    trend
+}
+
+/// `values` drawn as a line of bars, each as high as its value relative to the others.
+pub fn sparkline(values: &[Duration]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let (Some(low), Some(high)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = (*high - *low).as_secs_f64();
+    values
+        .iter()
+        .map(|value| match (*value - *low).as_secs_f64() {
+            _ if range == 0.0 => BARS[0],
+            above => BARS[(above / range * 7.0).round() as usize],
+        })
+        .collect()
+}
+
+/// Print a table of `comparisons`, marking each step that got more than `threshold` percent
+/// slower, and return how many did.
+pub fn print_diff(comparisons: &[Comparison], threshold: f64) -> usize {
+    let mut regressions = 0;
+    println!(
+        "{:>3}  {:<8}  {:<6}  {:<8}  {:>11}  {:>11}  {:>9}  Trend",
+        "Day", "Variant", "Step", "Input", "Baseline", "Current", "Change"
+    );
+    for comparison in comparisons {
+        let change = comparison.change();
+        let flag = if change > threshold {
+            regressions += 1;
+            "  REGRESSED"
+        } else if change < -threshold {
+            "  faster"
+        } else {
+            ""
+        };
+        let line = format!(
+            "{:>3}  {:<8}  {:<6}  {:<8}  {:>11}  {:>11}  {:>+8.1}%  {:<10}{}",
+            comparison.day,
+            comparison.variant,
+            comparison.part,
+            comparison.input,
+            format_duration(comparison.baseline),
+            format_duration(comparison.current),
+            change,
+            sparkline(&comparison.trend),
+            flag
+        );
+        println!("{}", line.trim_end());
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(commit: &str, part: &str, median_ms: u64) -> Record {
+        let median = Duration::from_millis(median_ms);
+        Record {
+            time: 1671235200,
+            commit: commit.to_owned(),
+            machine: "bench-box".to_owned(),
+            day: 8,
+            part: part.to_owned(),
+            variant: "default".to_owned(),
+            input: "real".to_owned(),
+            stats: Stats {
+                runs: 20,
+                min: median,
+                median,
+                mean: median,
+                p95: median,
+                std_dev: Duration::ZERO,
+            },
+        }
+    }
+
+    fn example() -> Vec<Record> {
+        vec![
+            record("aaaa1111", "part 1", 10),
+            record("aaaa1111", "part 2", 20),
+            record("bbbb2222", "part 1", 12),
+            record("cccc3333", "part 1", 15),
+            record("cccc3333", "part 1", 9),
+            record("cccc3333", "part 2", 30),
+        ]
+    }
+
+    #[test]
+    fn check_history_round_trip() {
+        let records = example();
+        let text: String = records.iter().map(|record| format!("{}\n", record.to_json())).collect();
+        assert_eq!(parse(&text), Ok(records));
+        assert_eq!(parse("{\"day\": 8}\n"), Err("line 1: expected a bench record".to_owned()));
+    }
+
+    #[test]
+    fn check_history_compare() {
+        let records = example();
+        assert_eq!(commits(&records), ["aaaa1111", "bbbb2222", "cccc3333"]);
+        assert_eq!(find_commit(&records, "bb"), Ok("bbbb2222"));
+        assert!(find_commit(&records, "dd").is_err());
+
+        // The last timing at each commit counts
+        let comparisons = compare(&records, "aaaa1111", "cccc3333");
+        let ms = |values: &[u64]| values.iter().map(|&v| Duration::from_millis(v)).collect::<Vec<_>>();
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!((comparisons[0].part.as_str(), comparisons[0].trend.clone()), ("part 1", ms(&[10, 12, 9])));
+        assert_eq!(comparisons[1].change().round(), 50.0);
+
+        // Steps that weren't benched at the baseline can't be compared
+        assert_eq!(compare(&records, "bbbb2222", "cccc3333").len(), 1);
+
+        assert_eq!(day_trend(&records, 8, "default", "real", 2), ms(&[12, 39]));
+        assert!(day_trend(&records, 8, "default", "example1", 2).is_empty());
+    }
+
+    #[test]
+    fn check_history_sparkline() {
+        let ms = |values: &[u64]| values.iter().map(|&v| Duration::from_millis(v)).collect::<Vec<_>>();
+        assert_eq!(sparkline(&ms(&[10, 17, 24])), "▁▅█");
+        assert_eq!(sparkline(&ms(&[5, 5])), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
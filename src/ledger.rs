@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use aoc2022_rs_jtgs::answers::Answers;
+use aoc2022_rs_jtgs::solution::Answer;
+
+/// Where the record of submitted answers is kept: `submissions.json` in the repository, next to
+/// `aoc.json` (and, like it, not checked in).  It's kept out of `inputs`, which the build watches.
+pub fn ledger_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("submissions.json")
+}
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, with no hint as to which way.
+    Wrong,
+}
+
+impl Verdict {
+    pub fn name(self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Verdict> {
+        [Verdict::Correct, Verdict::TooHigh, Verdict::TooLow, Verdict::Wrong]
+            .into_iter()
+            .find(|verdict| verdict.name() == name)
+    }
+}
+
+/// One answer that was submitted, and what the server made of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission {
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+/// Every answer submitted for each part of each day, in the order they were submitted, so that an
+/// answer already known to be wrong needn't be submitted again.
+///
+/// The file is a JSON object keyed by day, like the answers file, but each part holds a list:
+///
+/// ```json
+/// { "15": { "part2": [{ "answer": 5607466, "verdict": "too-low" }] } }
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Ledger {
+    submissions: BTreeMap<(usize, usize), Vec<Submission>>,
+}
+
+impl Ledger {
+    /// Load the ledger, which is empty if nothing has been submitted yet.
+    pub fn load() -> Result<Ledger, String> {
+        let path = ledger_path();
+        match fs::read_to_string(&path) {
+            Ok(text) => Ledger::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Ledger::default()),
+            Err(e) => Err(format!("can't read {} ({})", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = ledger_path();
+        fs::write(&path, format!("{:#}\n", self.to_json()))
+            .map_err(|e| format!("can't write {} ({})", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Ledger, String> {
+        let json: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let days = json.as_object().ok_or("expected an object keyed by day")?;
+
+        let mut ledger = Ledger::default();
+        for (day, parts) in days {
+            let day_number = day.parse().map_err(|_| format!("`{}` isn't a day number", day))?;
+            let parts = parts
+                .as_object()
+                .ok_or_else(|| format!("day {}: expected an object with part1 and/or part2", day))?;
+            for (part, submissions) in parts {
+                let part_number = match part.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => return Err(format!("day {}: `{}` should be part1 or part2", day, part)),
+                };
+                let bad = || format!("day {} {}: expected a list of answers and verdicts", day, part);
+                for submission in submissions.as_array().ok_or_else(bad)? {
+                    let answer = Answer::from_json(&submission["answer"]).ok_or_else(bad)?;
+                    let verdict = submission["verdict"].as_str().and_then(Verdict::from_name).ok_or_else(bad)?;
+                    ledger.record(day_number, part_number, answer, verdict);
+                }
+            }
+        }
+        Ok(ledger)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut days = Map::new();
+        for ((day, part), submissions) in &self.submissions {
+            let submissions: Vec<Value> = submissions
+                .iter()
+                .map(|submission| json!({ "answer": submission.answer.to_json(), "verdict": submission.verdict.name() }))
+                .collect();
+            let parts = days.entry(day.to_string()).or_insert_with(|| json!({}));
+            parts[format!("part{}", part)] = Value::Array(submissions);
+        }
+        Value::Object(days)
+    }
+
+    pub fn record(&mut self, day: usize, part: usize, answer: Answer, verdict: Verdict) {
+        self.submissions.entry((day, part)).or_default().push(Submission { answer, verdict });
+    }
+
+    /// Everything submitted for `day` and `part`, oldest first.
+    pub fn submissions(&self, day: usize, part: usize) -> &[Submission] {
+        self.submissions.get(&(day, part)).map_or(&[], Vec::as_slice)
+    }
+
+    /// The answer the server accepted for `day` and `part`, if it has.
+    pub fn correct(&self, day: usize, part: usize) -> Option<&Answer> {
+        self.submissions(day, part)
+            .iter()
+            .find(|submission| submission.verdict == Verdict::Correct)
+            .map(|submission| &submission.answer)
+    }
+
+    /// Add each answer the server said was correct to `answers`, unless it has one for that part
+    /// already.
+    pub fn accept_correct(&self, answers: &mut Answers) {
+        for &(day, part) in self.submissions.keys() {
+            if let Some(answer) = self.correct(day, part) {
+                answers.accept(day, part, answer.clone());
+            }
+        }
+    }
+
+    /// What the answer to `day` and `part` must lie between, from the answers that were too low and
+    /// too high: the highest that was too low, and the lowest that was too high.
+    pub fn bounds(&self, day: usize, part: usize) -> (Option<i128>, Option<i128>) {
+        let answers = |verdict| {
+            self.submissions(day, part)
+                .iter()
+                .filter(move |submission| submission.verdict == verdict)
+                .filter_map(|submission| submission.answer.as_integer())
+        };
+        (answers(Verdict::TooLow).max(), answers(Verdict::TooHigh).min())
+    }
+
+    /// Check whether submitting `answer` for `day` and `part` could be any use, returning why not if
+    /// it's already known to be wrong (or the part has already been solved).
+    pub fn check(&self, day: usize, part: usize, answer: &Answer) -> Result<(), String> {
+        if let Some(correct) = self.correct(day, part) {
+            return Err(format!("this part has already been solved, with {}", correct));
+        }
+        if let Some(submission) = self.submissions(day, part).iter().find(|s| &s.answer == answer) {
+            return Err(format!("{} has already been submitted, and was {}", answer, describe(submission.verdict)));
+        }
+        if let Some(n) = answer.as_integer() {
+            match self.bounds(day, part) {
+                (Some(low), _) if n <= low => return Err(format!("{} was too low, so {} is too", low, n)),
+                (_, Some(high)) if n >= high => return Err(format!("{} was too high, so {} is too", high, n)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Why `answer` is known to be wrong for `day` and `part`, if it is.
+    pub fn known_wrong(&self, day: usize, part: usize, answer: &Answer) -> Option<String> {
+        match self.correct(day, part) {
+            Some(_) => None,
+            None => self.check(day, part, answer).err(),
+        }
+    }
+}
+
+// A verdict as it would finish a sentence
+fn describe(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Correct => "correct",
+        Verdict::TooHigh => "too high",
+        Verdict::TooLow => "too low",
+        Verdict::Wrong => "wrong",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Ledger {
+        let mut ledger = Ledger::default();
+        ledger.record(15, 2, Answer::Int(5607466), Verdict::TooLow);
+        ledger.record(15, 2, Answer::Int(13743542639657), Verdict::TooHigh);
+        ledger.record(15, 2, Answer::Int(10000000), Verdict::TooLow);
+        ledger.record(15, 2, Answer::Int(12000000), Verdict::Wrong);
+        ledger.record(10, 2, Answer::from("PAPKFKEL"), Verdict::Wrong);
+        ledger.record(10, 2, Answer::from("PAPKFKEJ"), Verdict::Correct);
+        ledger
+    }
+
+    #[test]
+    fn check_ledger_round_trip() {
+        let ledger = example();
+        assert_eq!(Ledger::parse(&ledger.to_json().to_string()), Ok(example()));
+        assert_eq!(ledger.submissions(15, 2).len(), 4);
+        assert!(ledger.submissions(15, 1).is_empty());
+        assert!(Ledger::parse(r#"{"15": {"part2": [{"answer": 3, "verdict": "meh"}]}}"#).is_err());
+    }
+
+    #[test]
+    fn check_ledger_refuses_known_wrong_answers() {
+        let ledger = example();
+        assert_eq!(ledger.bounds(15, 2), (Some(10000000), Some(13743542639657)));
+        assert_eq!(ledger.check(15, 2, &Answer::Int(11000000)), Ok(()));
+        assert!(ledger.check(15, 2, &Answer::Int(12000000)).unwrap_err().contains("was wrong"));
+        assert!(ledger.check(15, 2, &Answer::Int(9999999)).unwrap_err().contains("too low"));
+        assert!(ledger.check(15, 2, &Answer::BigInt(1 << 70)).unwrap_err().contains("too high"));
+        assert!(ledger.check(15, 1, &Answer::Int(12000000)).is_ok());
+
+        // Once a part is solved there's nothing more to submit, but only wrong answers are wrong
+        assert_eq!(ledger.correct(10, 2), Some(&Answer::from("PAPKFKEJ")));
+        assert!(ledger.check(10, 2, &Answer::from("PAPKFKEJ")).unwrap_err().contains("already been solved"));
+        assert_eq!(ledger.known_wrong(10, 2, &Answer::from("PAPKFKEL")), None);
+        assert!(ledger.known_wrong(15, 2, &Answer::Int(12000000)).is_some());
+    }
+}
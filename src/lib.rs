@@ -0,0 +1,40 @@
+//! Solutions to the Advent of Code 2022 puzzles.
+//!
+//! Each day's solution lives in its own module (`day01` onwards, plus `day00`, which checks the
+//! installation), as a unit struct implementing [`solution::Solution`].  The types that a day's
+//! input parses into, such as [`day13::Packet`] and [`day15::Sensor`], are public too, so other
+//! tools can reuse them:
+//!
+//! ```
+//! use aoc2022_rs_jtgs::day13::Packet;
+//!
+//! let left: Packet = "[1,[2,[3]]]".parse().unwrap();
+//! let right: Packet = "[[1],4]".parse().unwrap();
+//! assert!(left < right);
+//! ```
+//!
+//! To run a solution, parse the input and hand the result to each part:
+//!
+//! ```no_run
+//! use aoc2022_rs_jtgs::day01::Day01;
+//! use aoc2022_rs_jtgs::solution::Solution;
+//!
+//! let input = aoc2022_rs_jtgs::helpers::load_input(1);
+//! let parsed = Day01.parse(&input).unwrap();
+//! println!("{}", Day01.part1(&parsed).unwrap());
+//! ```
+//!
+//! [`registry`] holds every day's solution behind the type-erased [`solution::Solver`] trait, for
+//! code (like the `aoc2022-rs-jtgs` runner) that picks days at run time.
+
+// A `pub mod dayNN;` for each src/dayNN.rs, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/day_modules.rs"));
+
+pub mod answer;
+pub mod answers;
+pub mod error;
+#[cfg(test)]
+mod examples;
+pub mod helpers;
+pub mod registry;
+pub mod solution;
@@ -0,0 +1,119 @@
+use std::env;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// The environment variable saying what to log: a comma-separated list of levels (`off`, `error`,
+/// `warn`, `info`, `debug` or `trace`), each either for every day, or for one day, as in
+/// `day16=debug`.  A level for one day overrides the level for every day.
+pub const LOG_VAR: &str = "AOC_LOG";
+
+/// What's logged when neither `$AOC_LOG` nor `-v`/`-q` says otherwise: only warnings and errors,
+/// which solutions don't normally give, so nothing.
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Warn;
+
+/// How much to log, for every day and for particular days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    level: LevelFilter,
+    /// Levels for particular targets, each the last part of a module path, e.g. `day16`.
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    pub fn parse(spec: &str) -> Result<Filter, String> {
+        let mut filter = Filter {
+            level: DEFAULT_LEVEL,
+            targets: Vec::new(),
+        };
+        let level = |name: &str| {
+            name.parse::<LevelFilter>()
+                .map_err(|_| format!("`{}` isn't a log level; try off, error, warn, info, debug or trace", name))
+        };
+
+        for directive in spec.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, name)) => filter.targets.push((target.trim().to_owned(), level(name.trim())?)),
+                None => filter.level = level(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    /// The filter as `$AOC_LOG` would give it.
+    pub fn to_spec(&self) -> String {
+        let mut directives = vec![self.level.to_string().to_lowercase()];
+        for (target, level) in &self.targets {
+            directives.push(format!("{}={}", target, level.to_string().to_lowercase()));
+        }
+        directives.join(",")
+    }
+
+    /// The level for messages from `target`, a module path such as `aoc2022_rs_jtgs::day16`.
+    fn level_for(&self, target: &str) -> LevelFilter {
+        let name = target.rsplit("::").next().unwrap_or(target);
+        self.targets
+            .iter()
+            .rev()
+            .find(|(t, _)| t == name || t == target)
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    // The most that's logged for anything
+    fn max_level(&self) -> LevelFilter {
+        self.targets.iter().map(|(_, level)| *level).fold(self.level, std::cmp::max)
+    }
+}
+
+// Writes each message that gets through the filter to stderr, so that stdout is only ever results
+struct Logger {
+    filter: Filter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            // Messages from the solutions are labelled with just the day
+            let target = record.target();
+            let name = target.strip_prefix(concat!(env!("CARGO_CRATE_NAME"), "::")).unwrap_or(target);
+            eprintln!("[{} {}] {}", record.level(), name, record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Start logging, as `$AOC_LOG` says, except that `level` (from `-v` or `-q`) replaces its level
+/// for every day.  The result is put back in `$AOC_LOG`, so that child processes log the same.
+pub fn init(level: Option<LevelFilter>) -> Result<(), String> {
+    let spec = env::var(LOG_VAR).unwrap_or_default();
+    let mut filter = Filter::parse(&spec).map_err(|e| format!("{}: {}", LOG_VAR, e))?;
+    if let Some(level) = level {
+        filter.level = level;
+        env::set_var(LOG_VAR, filter.to_spec());
+    }
+
+    log::set_max_level(filter.max_level());
+    log::set_boxed_logger(Box::new(Logger { filter })).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_logging_filter() {
+        let filter = Filter::parse("info, day16=trace,day07=off").unwrap();
+        assert_eq!(filter.level_for("aoc2022_rs_jtgs::day16"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("aoc2022_rs_jtgs::day07"), LevelFilter::Off);
+        assert_eq!(filter.level_for("aoc2022_rs_jtgs::day08"), LevelFilter::Info);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+        assert_eq!(Filter::parse(&filter.to_spec()), Ok(filter));
+
+        assert_eq!(Filter::parse("").unwrap().level_for("aoc2022_rs_jtgs::day16"), DEFAULT_LEVEL);
+        assert!(Filter::parse("day16=loud").is_err());
+    }
+}
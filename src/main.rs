@@ -1,90 +1,472 @@
-mod day00;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-pub mod helpers;
+mod alloc_stats;
+mod bench;
+mod cli;
+mod client;
+mod history;
+mod ledger;
+mod logging;
+mod output;
+mod report;
+mod runner;
+mod scaffold;
+mod status;
+mod watch;
 
 use std::env;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
 
-use helpers::load_input;
-
-type DayFunction = fn(&str) -> (String, String);
-static DAY_FUNCTIONS: [DayFunction; 26] = [
-    day00::day00,
-    day01::day01,
-    day02::day02,
-    day03::day03,
-    day04::day04,
-    day05::day05,
-    day06::day06,
-    day07::day07,
-    day08::day08,
-    day09::day09,
-    day10::day10,
-    day11::day11,
-    day12::day12,
-    day13::day13,
-    day14::day14,
-    day15::day15,
-    day16::day16,
-    day17::day17,
-    day18::day18,
-    day19::day19,
-    day20::day20,
-    day21::day21,
-    day22::day22,
-    day23::day23,
-    day24::day24,
-    day25::day25,
-];
+use aoc2022_rs_jtgs::answers::{Answers, Expected, Verdict};
+use aoc2022_rs_jtgs::helpers::{input_dir, input_names, REAL_INPUT};
+use aoc2022_rs_jtgs::registry;
+
+use cli::{
+    BenchOptions, Command, FetchOptions, InputSource, NewOptions, Parts, PerfDiffOptions, ReportOptions, RunOptions,
+    SubmitOptions, Variants, VerifyOptions,
+};
+use client::{Client, Config, Fetched, Outcome};
+use ledger::Ledger;
+use output::{Format, Printer, Status};
+use runner::{each_variant, read_input, run_day, run_days, select_variants, variant_label};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let min_day: usize;
-    let max_day: usize;
-    if args.len() < 2 {
-        min_day = 1;
-        max_day = DAY_FUNCTIONS.len() - 1;
-    } else {
-        min_day = args[1]
-            .parse::<usize>()
-            .expect("Please provide the day number as an integer.");
-        if min_day > DAY_FUNCTIONS.len() {
-            panic!("Invalid day specified.");
-        }
-        max_day = min_day;
-    }
-
-    for day in min_day..=max_day {
-        println!("Day {}", day);
-        let input_lines = load_input(day);
-        let start_time = std::time::Instant::now();
-        let (part1, part2) = DAY_FUNCTIONS[day](&input_lines);
-        let elapsed = start_time.elapsed().as_micros();
-        println!("Part 1: {}\nPart 2: {}", part1, part2);
-        println!("{}.{:03}ms", elapsed / 1000, elapsed % 1000);
-        println!("----------");
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let parsed = cli::take_log_level(&mut args).and_then(|level| Ok((level, cli::parse_args(&args)?)));
+    let command = match parsed {
+        Ok((level, command)) => {
+            if let Err(e) = logging::init(level) {
+                eprintln!("error: {}", e);
+                process::exit(2);
+            }
+            command
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Help => print!("{}", cli::USAGE),
+        Command::List => {
+            for solution in registry::all() {
+                let variants: Vec<&str> = registry::variants(solution.day()).iter().map(|v| v.name).collect();
+                println!(
+                    "{} day {:>2}: {} [{}]{}",
+                    solution.year(),
+                    solution.day(),
+                    solution.title(),
+                    input_names(solution.day()).join(", "),
+                    match variants.len() {
+                        1 => String::new(),
+                        _ => format!(" (variants: {})", variants.join(", ")),
+                    }
+                );
+            }
+        }
+        Command::Run(options) => run(&options),
+        Command::Bench(options) => bench(&options),
+        Command::PerfDiff(options) => perf_diff(&options),
+        Command::Verify(options) => verify(&options),
+        Command::Report(options) => report(&options),
+        Command::New(options) => new(&options),
+        Command::Watch(options) => watch::watch(Path::new(env!("CARGO_MANIFEST_DIR")), &options),
+        Command::Fetch(options) => fetch(&options),
+        Command::Submit(options) => submit(&options),
+        Command::Status => status::print_status(&load_answers(&load_ledger())),
+        Command::RunChild(options) => {
+            for &day in &options.days {
+                println!("{}", run_day(day, &options).to_json());
+            }
+        }
+    }
+}
+
+fn check_release(options: &RunOptions) {
+    if options.release_check && cfg!(debug_assertions) {
+        eprintln!("error: this is a debug build, so timings won't mean much; use `cargo run --release`");
+        process::exit(1);
+    }
+}
+
+fn run(options: &RunOptions) {
+    check_release(options);
+
+    let started = Instant::now();
+    let mut printer = Printer::new(options.format);
+    let results = run_days(options, |result| printer.print(result));
+    printer.finish();
+    if options.format == Format::Text && results.len() > 1 {
+        output::print_summary(&results, started.elapsed());
+    }
+
+    let disagreements = output::disagreements(&results);
+    if !disagreements.is_empty() {
+        eprintln!("error: the variants don't agree:");
+        for disagreement in disagreements {
+            eprintln!("  {}", disagreement);
+        }
+        process::exit(1);
+    }
+}
+
+fn bench(options: &BenchOptions) {
+    check_release(&options.run);
+    let parts = [options.run.parts.includes(1), options.run.parts.includes(2)];
+    let mut results = Vec::new();
+    let mut records = Vec::new();
+    let stamp = history::Stamp::now(Path::new(env!("CARGO_MANIFEST_DIR")));
+
+    for &day in &options.run.days {
+        let variants = match select_variants(day, &options.run.variant) {
+            Ok(variants) if variants.is_empty() => {
+                eprintln!("Day {}: no solution module", day);
+                continue;
+            }
+            Ok(variants) => variants,
+            Err(e) => {
+                eprintln!("Day {}: {}", day, e);
+                continue;
+            }
+        };
+        let (input_lines, params) = match read_input(day, &options.run.input) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Day {}: {}", day, e);
+                continue;
+            }
+        };
+
+        for variant in variants {
+            let label = variant_label(day, &variant);
+            match bench::bench_day(variant.solver, &input_lines, &params, parts, options.warmup, options.repeat) {
+                Ok(result) => {
+                    if let InputSource::Named(input) = &options.run.input {
+                        records.extend(stamp.records(&result, variant.name, input));
+                    }
+                    results.push(bench::DayBench { variant: label, ..result });
+                }
+                Err(e) => eprintln!("Day {}{}: {}", day, label.map(|v| format!(" ({})", v)).unwrap_or_default(), e),
+            }
+        }
+    }
+
+    bench::print_table(&results);
+
+    // Debug builds are too slow for their timings to be worth keeping
+    if options.record && !records.is_empty() {
+        if cfg!(debug_assertions) {
+            eprintln!("warning: not added to the history, since this is a debug build; use `cargo run --release`");
+        } else {
+            match history::append(&records) {
+                Ok(()) => println!("\nAdded to the history as {} on {}", stamp.commit, stamp.machine),
+                Err(e) => eprintln!("error: {}", e),
+            }
+        }
+    }
+}
+
+fn perf_diff(options: &PerfDiffOptions) {
+    let machine = options.machine.clone().unwrap_or_else(history::machine_name);
+    let records: Vec<history::Record> = history::load()
+        .unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        })
+        .into_iter()
+        .filter(|record| record.machine == machine)
+        .filter(|record| options.days.as_ref().is_none_or(|days| days.contains(&record.day)))
+        .collect();
+
+    // The latest timings are compared with the baseline's
+    let Some(current) = records.last().map(|record| record.commit.as_str()) else {
+        eprintln!("error: nothing has been benched on {} yet (or not on those days)", machine);
+        process::exit(1);
+    };
+    let baseline = match &options.baseline {
+        Some(name) => history::find_commit(&records, name),
+        None => {
+            let commits = history::commits(&records);
+            let before = commits.iter().take_while(|commit| **commit != current).last().copied();
+            before.ok_or(format!("only {} has been benched on {}, so there's nothing to compare", current, machine))
+        }
+    };
+    let baseline = baseline.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    });
+
+    println!("Comparing {} with {} on {}\n", current, baseline, machine);
+    let regressions = history::print_diff(&history::compare(&records, baseline, current), options.threshold);
+    if regressions > 0 {
+        println!("\n{} step(s) regressed by more than {}%", regressions, options.threshold);
+        process::exit(1);
+    }
+}
+
+// The accepted answers: those in the answers file, and any others the ledger says were correct
+fn load_answers(ledger: &Ledger) -> Answers {
+    let mut answers = Answers::load().unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    });
+    ledger.accept_correct(&mut answers);
+    answers
+}
+
+fn load_ledger() -> Ledger {
+    Ledger::load().unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    })
+}
+
+fn verify(options: &VerifyOptions) {
+    let ledger = load_ledger();
+    let answers = load_answers(&ledger);
+    let days = options.days.clone().unwrap_or_else(|| answers.days());
+    let (mut passed, mut failed, mut missing, mut skipped) = (0, 0, 0, 0);
+
+    for day in days {
+        // The real input is checked against the answers file, and any other inputs against the
+        // .expected files beside them
+        let mut cases = vec![(REAL_INPUT.to_owned(), None)];
+        if options.all_inputs {
+            for name in input_names(day).into_iter().filter(|name| name != REAL_INPUT) {
+                match Expected::load(day, &name) {
+                    Ok(Some(expected)) => cases.push((name, Some(expected.answers))),
+                    Ok(None) => {}
+                    Err(e) => {
+                        failed += 1;
+                        println!("Day {:>2} ({}): FAIL\n  {}", day, name, e);
+                    }
+                }
+            }
+        }
+
+        for (name, expected) in cases {
+            let run_options = RunOptions {
+                days: vec![day],
+                parts: options.parts,
+                input: InputSource::Named(name.clone()),
+                variant: options.variant.clone(),
+                release_check: false,
+                format: Format::Text,
+                jobs: 1,
+                timeout: None,
+            };
+            let results: Vec<_> = each_variant(day, &run_options).iter().map(|run| run_day(day, run)).collect();
+            // Variants that disagree can't all be right, even if the right answer isn't known
+            for disagreement in output::disagreements(&results) {
+                failed += 1;
+                match name.as_str() {
+                    REAL_INPUT => println!("Variants: FAIL\n  {}", disagreement),
+                    _ => println!("Variants ({}): FAIL\n  {}", name, disagreement),
+                }
+            }
+
+            for result in &results {
+                let label = match (name.as_str(), result.variant) {
+                    (REAL_INPUT, None) => String::new(),
+                    (REAL_INPUT, Some(variant)) => format!(" [{}]", variant),
+                    (_, None) => format!(" ({})", name),
+                    (_, Some(variant)) => format!(" ({}) [{}]", name, variant),
+                };
+
+                for part in &result.parts {
+                    print!("Day {:>2} part {}{}: ", day, part.part, label);
+                    if part.status == Status::Unsolved {
+                        skipped += 1;
+                        println!("SKIPPED (unsolved)");
+                        continue;
+                    }
+                    match expected.as_ref().unwrap_or(&answers).check(day, part.part, &part.answer) {
+                        Verdict::Pass => {
+                            passed += 1;
+                            println!("PASS");
+                        }
+                        Verdict::Fail { expected, actual } => {
+                            failed += 1;
+                            println!("FAIL");
+                            println!("  expected: {}", expected);
+                            match actual {
+                                Ok(answer) => println!("  actual:   {}", answer),
+                                Err(e) => println!("  actual:   error: {}", e),
+                            }
+                        }
+                        Verdict::Missing => {
+                            // The right answer to the real input may not be known, but it may be known
+                            // that this one is wrong
+                            let known_wrong = match (&expected, &part.answer) {
+                                (None, Ok(answer)) => ledger.known_wrong(day, part.part, answer),
+                                _ => None,
+                            };
+                            match known_wrong {
+                                Some(reason) => {
+                                    failed += 1;
+                                    println!("FAIL\n  {}", reason);
+                                }
+                                None => {
+                                    missing += 1;
+                                    println!("MISSING");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing, {} skipped", passed, failed, missing, skipped);
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+fn report(options: &ReportOptions) {
+    let ledger = load_ledger();
+    let answers = load_answers(&ledger);
+    let stamp = history::Stamp::now(Path::new(env!("CARGO_MANIFEST_DIR")));
+    let history: Vec<history::Record> = history::load()
+        .unwrap_or_else(|e| {
+            eprintln!("warning: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .filter(|record| record.machine == stamp.machine)
+        .collect();
+
+    let run_options = RunOptions {
+        days: options.days.clone(),
+        parts: Parts::Both,
+        input: InputSource::real(),
+        variant: Variants::Default,
+        release_check: false,
+        format: Format::Text,
+        jobs: options.jobs,
+        timeout: options.timeout,
+    };
+    let results = run_days(&run_options, |result| eprintln!("Ran day {}", result.day));
+
+    let context = report::Context {
+        answers: &answers,
+        ledger: &ledger,
+        history: &history,
+        commit: &stamp.commit,
+        time: stamp.time,
+    };
+    match std::fs::write(&options.html, report::render(&results, &context)) {
+        Ok(()) => println!("Wrote {}", options.html.display()),
+        Err(e) => {
+            eprintln!("error: can't write {} ({})", options.html.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+fn new(options: &NewOptions) {
+    match scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), options.day, options.year) {
+        Ok(created) => {
+            for path in created {
+                println!("Wrote {}", path.display());
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+// A client for the server in the config, or exit if it can't be set up
+fn connect() -> Client {
+    Config::load(Path::new(env!("CARGO_MANIFEST_DIR")))
+        .and_then(|config| Client::new(&config))
+        .unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        })
+}
+
+// The year that `day`'s puzzle is from: as given, or else the year of its solution
+fn puzzle_year(day: usize, year: Option<u16>) -> u16 {
+    year.or_else(|| registry::get(day).map(|solution| solution.year()))
+        .unwrap_or(cli::DEFAULT_YEAR)
+}
+
+fn fetch(options: &FetchOptions) {
+    let client = connect();
+    let mut failed = false;
+
+    for &day in &options.days {
+        let path = input_dir(day).join(format!("{}.txt", REAL_INPUT));
+        match client.fetch_input(puzzle_year(day, options.year), day, &path) {
+            Ok(Fetched::Downloaded) => println!("Day {}: downloaded {}", day, path.display()),
+            Ok(Fetched::Cached) => println!("Day {}: already have {}", day, path.display()),
+            Err(e) => {
+                failed = true;
+                eprintln!("Day {}: {}", day, e);
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn submit(options: &SubmitOptions) {
+    let run_options = RunOptions {
+        days: vec![options.day],
+        parts: if options.part == 1 { Parts::One } else { Parts::Two },
+        input: InputSource::real(),
+        variant: Variants::Default,
+        release_check: false,
+        format: Format::Text,
+        jobs: 1,
+        timeout: None,
+    };
+    let result = run_day(options.day, &run_options);
+    let answer = match result.parts.first().map(|part| &part.answer) {
+        Some(Ok(answer)) if answer.is_solved() => answer,
+        Some(Ok(_)) => {
+            eprintln!("error: day {} part {} isn't implemented yet", options.day, options.part);
+            process::exit(1);
+        }
+        Some(Err(e)) => {
+            eprintln!("error: day {} part {} failed: {}", options.day, options.part, e);
+            process::exit(1);
+        }
+        None => unreachable!("run_day gives a result for each part asked for"),
+    };
+
+    let mut ledger = load_ledger();
+    if let Err(reason) = ledger.check(options.day, options.part, answer) {
+        println!("Not submitted: {}", reason);
+        process::exit(1);
+    }
+
+    println!("Submitting {} for day {} part {}", answer, options.day, options.part);
+    let outcome = connect().submit(puzzle_year(options.day, options.year), options.day, options.part, answer);
+    if let Some(verdict) = outcome.as_ref().ok().and_then(Outcome::verdict) {
+        ledger.record(options.day, options.part, answer.clone(), verdict);
+        if let Err(e) = ledger.save() {
+            eprintln!("error: {}", e);
+        }
+    }
+    match outcome {
+        Ok(Outcome::Correct) => println!("That's the right answer!"),
+        Ok(Outcome::TooHigh) => println!("Wrong: too high"),
+        Ok(Outcome::TooLow) => println!("Wrong: too low"),
+        Ok(Outcome::Wrong) => println!("Wrong"),
+        Ok(Outcome::TooSoon(message)) => println!("Not submitted: {}", message),
+        Ok(Outcome::AlreadySolved) => println!("Not submitted: that part is already solved, or isn't open yet"),
+        Ok(Outcome::Unrecognised(message)) => println!("Unexpected reply: {}", message),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
     }
 }
@@ -0,0 +1,503 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use aoc2022_rs_jtgs::helpers::format_duration;
+use aoc2022_rs_jtgs::solution::Answer;
+
+use crate::alloc_stats::{format_bytes, AllocStats};
+
+/// How the runner should print its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// For people: the answers, with the time taken for each day.
+    Text,
+    /// A single JSON array with one object per part, printed once every day has run.
+    Json,
+    /// A header row, then one row per part.
+    Csv,
+    /// One JSON object per line, per part, printed as soon as each day has run.
+    JsonLines,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            "jsonl" => Some(Format::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+/// How running one part went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// The part couldn't be run, or failed.
+    Error,
+    /// The part hasn't been solved yet, so has no answer.  Named `stub`, as it was before parts
+    /// could say they were unsolved.
+    Unsolved,
+    /// The part took longer than it was allowed to.
+    Timeout,
+    /// There's no solution for the day at all.
+    NotImplemented,
+}
+
+impl Status {
+    pub fn name(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Error => "error",
+            Status::Unsolved => "stub",
+            Status::Timeout => "timeout",
+            Status::NotImplemented => "not-implemented",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Status> {
+        [Status::Ok, Status::Error, Status::Unsolved, Status::Timeout, Status::NotImplemented]
+            .into_iter()
+            .find(|status| status.name() == name)
+    }
+}
+
+/// The result of running one part of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartResult {
+    pub part: usize,
+    pub status: Status,
+    /// The answer, or why there isn't one.
+    pub answer: Result<Answer, String>,
+    /// Time taken by this part alone, not counting the parse.
+    pub elapsed: Duration,
+    /// What this part allocated, if that's being counted (by the `alloc-stats` feature).
+    pub alloc: Option<AllocStats>,
+}
+
+/// The result of running one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayResult {
+    pub day: usize,
+    pub title: Option<&'static str>,
+    /// Which variant of the solution was run, if the day has more than one.
+    pub variant: Option<&'static str>,
+    /// Fingerprint of the input, if it could be read.
+    pub input_hash: Option<String>,
+    /// Why the day couldn't be run at all, e.g. because its input couldn't be parsed.  If this is
+    /// set, each part's answer is this error too.
+    pub error: Option<String>,
+    pub parts: Vec<PartResult>,
+    /// Time taken to parse the input and run all the parts.
+    pub elapsed: Duration,
+    /// What the parse and all the parts allocated, if that's being counted.
+    pub alloc: Option<AllocStats>,
+}
+
+impl DayResult {
+    /// Whether there's a solution for this day to run.
+    pub fn is_implemented(&self) -> bool {
+        !self.parts.iter().any(|part| part.status == Status::NotImplemented)
+    }
+
+    /// The whole result, for passing from a child process back to the runner.  (The title and
+    /// variant aren't included, since the runner knows them already.)
+    pub fn to_json(&self) -> Value {
+        let parts: Vec<Value> = self
+            .parts
+            .iter()
+            .map(|part| {
+                json!({
+                    "part": part.part,
+                    "status": part.status.name(),
+                    "answer": part.answer.as_ref().ok().map(Answer::to_json),
+                    "error": part.answer.as_ref().err(),
+                    "elapsed_ns": part.elapsed.as_nanos() as u64,
+                    "alloc": part.alloc.map(alloc_to_json),
+                })
+            })
+            .collect();
+        json!({
+            "day": self.day,
+            "input_hash": self.input_hash,
+            "error": self.error,
+            "parts": parts,
+            "elapsed_ns": self.elapsed.as_nanos() as u64,
+            "alloc": self.alloc.map(alloc_to_json),
+        })
+    }
+
+    /// Read back a result written by `to_json`.
+    pub fn from_json(value: &Value, title: Option<&'static str>, variant: Option<&'static str>) -> Option<DayResult> {
+        let string = |value: &Value| value.as_str().map(str::to_owned);
+        let elapsed = |value: &Value| value["elapsed_ns"].as_u64().map(Duration::from_nanos);
+
+        let mut parts = Vec::new();
+        for part in value["parts"].as_array()? {
+            parts.push(PartResult {
+                part: part["part"].as_u64()? as usize,
+                status: Status::from_name(part["status"].as_str()?)?,
+                answer: match string(&part["error"]) {
+                    Some(error) => Err(error),
+                    None => Ok(Answer::from_json(&part["answer"])?),
+                },
+                elapsed: elapsed(part)?,
+                alloc: alloc_from_json(&part["alloc"]),
+            });
+        }
+
+        Some(DayResult {
+            day: value["day"].as_u64()? as usize,
+            title,
+            variant,
+            input_hash: string(&value["input_hash"]),
+            error: string(&value["error"]),
+            parts,
+            elapsed: elapsed(value)?,
+            alloc: alloc_from_json(&value["alloc"]),
+        })
+    }
+}
+
+fn alloc_to_json(alloc: AllocStats) -> Value {
+    json!({ "allocations": alloc.allocations, "bytes": alloc.bytes, "peak": alloc.peak })
+}
+
+fn alloc_from_json(value: &Value) -> Option<AllocStats> {
+    Some(AllocStats {
+        allocations: value["allocations"].as_u64()?,
+        bytes: value["bytes"].as_u64()?,
+        peak: value["peak"].as_u64()?,
+    })
+}
+
+/// Prints results in the chosen format as each day finishes.
+pub struct Printer {
+    format: Format,
+    // Records held back until the end, for `Format::Json`
+    records: Vec<Value>,
+}
+
+impl Printer {
+    pub fn new(format: Format) -> Printer {
+        if format == Format::Csv {
+            println!(
+                "day,part,status,answer,elapsed_ms,input_hash,error,variant,allocations,bytes_allocated,peak_bytes"
+            );
+        }
+        Printer {
+            format,
+            records: Vec::new(),
+        }
+    }
+
+    pub fn print(&mut self, result: &DayResult) {
+        match self.format {
+            Format::Text => print_text(result),
+            Format::Json => self.records.extend(records(result)),
+            Format::JsonLines => {
+                for record in records(result) {
+                    println!("{}", record);
+                }
+            }
+            Format::Csv => {
+                for part in &result.parts {
+                    println!("{}", csv_row(result, part));
+                }
+            }
+        }
+    }
+
+    /// Print anything still held back.
+    pub fn finish(self) {
+        if self.format == Format::Json {
+            println!("{:#}", Value::Array(self.records));
+        }
+    }
+}
+
+fn print_text(result: &DayResult) {
+    let variant = result.variant.map(|variant| format!(" ({})", variant)).unwrap_or_default();
+    match result.title {
+        Some(title) => println!("Day {}: {}{}", result.day, title, variant),
+        None if result.is_implemented() => println!("Day {}{}", result.day, variant),
+        None => println!("Day {}: no solution module", result.day),
+    }
+    match &result.error {
+        Some(_) if !result.is_implemented() => {}
+        Some(e) => println!("Error: {}", e),
+        None => {
+            for part in &result.parts {
+                match &part.answer {
+                    // Show the picture, as well as any letters it could be read as
+                    Ok(answer @ Answer::Grid(rows)) => {
+                        match answer.ocr() {
+                            Some(letters) => println!("Part {}: {}", part.part, letters),
+                            None => println!("Part {}:", part.part),
+                        }
+                        for row in rows {
+                            println!("{}", row);
+                        }
+                    }
+                    Ok(answer) => println!("Part {}: {}", part.part, answer),
+                    Err(e) => println!("Part {}: error: {}", part.part, e),
+                }
+            }
+            println!("{}", format_duration(result.elapsed));
+            if let Some(alloc) = result.alloc {
+                println!("Memory: {}", alloc);
+                for part in &result.parts {
+                    if let Some(alloc) = part.alloc {
+                        println!("  part {}: {}", part.part, alloc);
+                    }
+                }
+            }
+        }
+    }
+    println!("----------");
+}
+
+/// Print a table of how each day went, then any failures, then the total time taken.  When days
+/// run in parallel, the wall-clock time will be less than the sum of the days' times.
+pub fn print_summary(results: &[DayResult], wall_clock: Duration) {
+    let failed = |part: &&PartResult| matches!(part.status, Status::Error | Status::Timeout);
+
+    let width = results.iter().map(|result| day_label(result).len()).max().unwrap_or(0).max(3);
+    // Only show the memory used if it was counted
+    let peak = |result: &DayResult| match result.alloc {
+        Some(alloc) => format!("  {:>10}", format_bytes(alloc.peak)),
+        None => String::new(),
+    };
+    let peak_header = match results.iter().any(|result| result.alloc.is_some()) {
+        true => format!("  {:>10}", "Peak"),
+        false => String::new(),
+    };
+    println!("{:>width$}  {:<18}  {:>12}{}", "Day", "Result", "Time", peak_header, width = width);
+    for result in results {
+        let outcome = match result.parts.iter().find(failed).map(|part| part.status) {
+            Some(Status::Timeout) => "TIMEOUT",
+            Some(_) => "FAILED",
+            None if !result.is_implemented() => "no solution module",
+            None if result.parts.iter().any(|part| part.status == Status::Unsolved) => "incomplete",
+            None => "ok",
+        };
+        println!(
+            "{:>width$}  {:<18}  {:>12}{}",
+            day_label(result),
+            outcome,
+            format_duration(result.elapsed),
+            peak(result),
+            width = width
+        );
+    }
+
+    let failures: Vec<String> = results
+        .iter()
+        .flat_map(|result| {
+            result.parts.iter().filter(failed).map(move |part| {
+                let error = part.answer.as_ref().err().map_or("", String::as_str);
+                format!("Day {} part {}: {}", day_label(result), part.part, error)
+            })
+        })
+        .collect();
+    if !failures.is_empty() {
+        println!("\nFailures:");
+        for failure in failures {
+            println!("  {}", failure);
+        }
+    }
+
+    let total: Duration = results.iter().map(|result| result.elapsed).sum();
+    println!(
+        "\nWall-clock time: {}  (sum of day times: {})",
+        format_duration(wall_clock),
+        format_duration(total)
+    );
+}
+
+// The day, and which variant of it was run if there's a choice
+fn day_label(result: &DayResult) -> String {
+    match result.variant {
+        Some(variant) => format!("{} ({})", result.day, variant),
+        None => result.day.to_string(),
+    }
+}
+
+/// Where different variants of the same day gave different answers, e.g. "Day 2 part 1: enum gave
+/// 15, table gave 14".  Parts that failed or haven't been solved aren't compared.
+pub fn disagreements(results: &[DayResult]) -> Vec<String> {
+    let mut disagreements = Vec::new();
+    for same_day in results.chunk_by(|a, b| a.day == b.day) {
+        for part in 1..=2 {
+            let answers: Vec<(&str, &Answer)> = same_day
+                .iter()
+                .filter_map(|result| {
+                    let answer = result.parts.iter().find(|p| p.part == part)?.answer.as_ref().ok()?;
+                    answer.is_solved().then_some((result.variant.unwrap_or("default"), answer))
+                })
+                .collect();
+            if answers.iter().any(|(_, answer)| *answer != answers[0].1) {
+                let gave: Vec<String> =
+                    answers.iter().map(|(variant, answer)| format!("{} gave {}", variant, answer)).collect();
+                disagreements.push(format!("Day {} part {}: {}", same_day[0].day, part, gave.join(", ")));
+            }
+        }
+    }
+    disagreements
+}
+
+// Milliseconds, to the nearest microsecond (like the text output)
+fn elapsed_ms(elapsed: Duration) -> f64 {
+    elapsed.as_micros() as f64 / 1000.0
+}
+
+/// One JSON object for each part in `result`.
+pub fn records(result: &DayResult) -> Vec<Value> {
+    result
+        .parts
+        .iter()
+        .map(|part| {
+            json!({
+                "day": result.day,
+                "part": part.part,
+                "status": part.status.name(),
+                "answer": part.answer.as_ref().ok().map(Answer::to_json),
+                "elapsed_ms": elapsed_ms(part.elapsed),
+                "input_hash": result.input_hash,
+                "error": part.answer.as_ref().err(),
+                "variant": result.variant,
+                "allocations": part.alloc.map(|alloc| alloc.allocations),
+                "bytes_allocated": part.alloc.map(|alloc| alloc.bytes),
+                "peak_bytes": part.alloc.map(|alloc| alloc.peak),
+            })
+        })
+        .collect()
+}
+
+fn csv_row(result: &DayResult, part: &PartResult) -> String {
+    let (answer, error) = match &part.answer {
+        Ok(answer) if answer.is_solved() => (answer.to_string(), ""),
+        Ok(_) => (String::new(), ""),
+        Err(e) => (String::new(), e.as_str()),
+    };
+    [
+        result.day.to_string(),
+        part.part.to_string(),
+        part.status.name().to_string(),
+        csv_field(&answer),
+        format!("{:.3}", elapsed_ms(part.elapsed)),
+        result.input_hash.clone().unwrap_or_default(),
+        csv_field(error),
+        result.variant.unwrap_or_default().to_owned(),
+        part.alloc.map(|alloc| alloc.allocations.to_string()).unwrap_or_default(),
+        part.alloc.map(|alloc| alloc.bytes.to_string()).unwrap_or_default(),
+        part.alloc.map(|alloc| alloc.peak.to_string()).unwrap_or_default(),
+    ]
+    .join(",")
+}
+
+// Quote a CSV field if it needs it (RFC 4180): some answers span several lines
+fn csv_field(field: &str) -> String {
+    if field.contains(['"', ',', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> DayResult {
+        DayResult {
+            day: 10,
+            title: Some("Cathode-Ray Tube"),
+            variant: None,
+            input_hash: Some("0123456789abcdef".to_owned()),
+            error: None,
+            parts: vec![
+                PartResult {
+                    part: 1,
+                    status: Status::Ok,
+                    answer: Ok(Answer::Int(13140)),
+                    elapsed: Duration::from_micros(1500),
+                    alloc: Some(AllocStats {
+                        allocations: 3,
+                        bytes: 4096,
+                        peak: 2048,
+                    }),
+                },
+                PartResult {
+                    part: 2,
+                    status: Status::Error,
+                    answer: Err("line 1, column 1: bad \"op\"".to_owned()),
+                    elapsed: Duration::ZERO,
+                    alloc: None,
+                },
+            ],
+            elapsed: Duration::from_micros(2000),
+            alloc: None,
+        }
+    }
+
+    #[test]
+    fn check_output_json_records() {
+        let records = records(&example());
+        assert_eq!(
+            records[0],
+            json!({
+                "day": 10,
+                "part": 1,
+                "status": "ok",
+                "answer": 13140,
+                "elapsed_ms": 1.5,
+                "input_hash": "0123456789abcdef",
+                "error": null,
+                "variant": null,
+                "allocations": 3,
+                "bytes_allocated": 4096,
+                "peak_bytes": 2048,
+            })
+        );
+        assert_eq!(records[1]["answer"], Value::Null);
+        assert_eq!(records[1]["status"], "error");
+    }
+
+    #[test]
+    fn check_output_round_trip() {
+        let result = example();
+        assert_eq!(DayResult::from_json(&result.to_json(), result.title, None), Some(result));
+    }
+
+    #[test]
+    fn check_output_csv_rows() {
+        let result = example();
+        assert_eq!(csv_row(&result, &result.parts[0]), "10,1,ok,13140,1.500,0123456789abcdef,,,3,4096,2048");
+        assert_eq!(
+            csv_row(&result, &result.parts[1]),
+            "10,2,error,,0.000,0123456789abcdef,\"line 1, column 1: bad \"\"op\"\"\",,,,"
+        );
+        assert_eq!(csv_field("#.\n.#"), "\"#.\n.#\"");
+    }
+
+    #[test]
+    fn check_output_disagreements() {
+        let variant = |variant, answer| DayResult {
+            variant: Some(variant),
+            parts: vec![PartResult {
+                answer: Ok(Answer::Int(answer)),
+                ..example().parts.remove(0)
+            }],
+            ..example()
+        };
+        assert!(disagreements(&[variant("naive", 13140), variant("fast", 13140), example()]).is_empty());
+        assert_eq!(
+            disagreements(&[variant("naive", 13140), variant("fast", 13141)]),
+            ["Day 10 part 1: naive gave 13140, fast gave 13141"]
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crate::solution::{Solver, Variant};
+
+/// Every day's solutions, as each `dayNN` module registers them (see
+/// [`register!`](crate::register)), in day order.  The list is generated by build.rs, so adding a
+/// day just means adding its module.
+static DAYS: &[&[Variant]] = include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+
+/// All registered solutions (the first variant of each day), in day order.
+pub fn all() -> impl Iterator<Item = &'static dyn Solver> {
+    DAYS.iter().map(|variants| variants[0].solver)
+}
+
+/// The solution for `day`, if there is one.  If the day has more than one variant, this is the
+/// first.
+pub fn get(day: usize) -> Option<&'static dyn Solver> {
+    all().find(|s| s.day() == day)
+}
+
+/// Every variant of `day`'s solution, the default first; empty if there's no solution.
+pub fn variants(day: usize) -> &'static [Variant] {
+    DAYS.iter().find(|variants| variants[0].solver.day() == day).copied().unwrap_or(&[])
+}
+
+/// The variant of `day`'s solution called `name`, if there is one.
+pub fn get_variant(day: usize, name: &str) -> Option<&'static dyn Solver> {
+    variants(day).iter().find(|variant| variant.name == name).map(|variant| variant.solver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_registry_days_unique_and_ordered() {
+        let days: Vec<usize> = all().map(|s| s.day()).collect();
+        assert!(days.windows(2).all(|w| w[0] < w[1]), "{:?}", days);
+    }
+
+    #[test]
+    fn check_registry_get() {
+        assert_eq!(get(8).map(|s| s.title()), Some("Treetop Tree House"));
+        assert!(get(26).is_none());
+        // Days that haven't been started have no module, so nothing registered
+        assert!(get(18).is_none());
+    }
+
+    #[test]
+    fn check_registry_variants() {
+        for variants in DAYS {
+            let day = variants[0].solver.day();
+            assert!(variants.iter().all(|variant| variant.solver.day() == day), "day {}", day);
+            for (i, variant) in variants.iter().enumerate() {
+                assert!(!variants[..i].iter().any(|v| v.name == variant.name), "day {}: {}", day, variant.name);
+            }
+        }
+
+        let names: Vec<&str> = variants(2).iter().map(|variant| variant.name).collect();
+        assert_eq!(names, ["enum", "table"]);
+        assert!(get_variant(2, "table").is_some());
+        assert!(get_variant(2, "quantum").is_none());
+        assert_eq!(variants(8).len(), 1);
+        assert!(variants(18).is_empty());
+    }
+}
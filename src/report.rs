@@ -0,0 +1,293 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use aoc2022_rs_jtgs::answers::{Answers, Verdict};
+use aoc2022_rs_jtgs::helpers::format_duration;
+use aoc2022_rs_jtgs::registry;
+use aoc2022_rs_jtgs::solution::Answer;
+
+use crate::history::{self, Record};
+use crate::ledger::Ledger;
+use crate::output::{DayResult, PartResult, Status};
+
+/// How many of the most recent commits each day's sparkline covers.
+const TREND_LENGTH: usize = 20;
+
+/// Everything that goes into a report, besides the results themselves.
+pub struct Context<'a> {
+    pub answers: &'a Answers,
+    pub ledger: &'a Ledger,
+    /// The bench history, for this machine.
+    pub history: &'a [Record],
+    /// The commit the code was built from.
+    pub commit: &'a str,
+    /// When the report was made, in seconds since the Unix epoch.
+    pub time: u64,
+}
+
+// Whether a part's answer is right, as far as is known: a CSS class, a label and any explanation
+fn check(day: usize, part: &PartResult, context: &Context) -> (&'static str, &'static str, String) {
+    match part.status {
+        Status::NotImplemented => return ("todo", "not started", String::new()),
+        Status::Unsolved => return ("todo", "to do", String::new()),
+        Status::Timeout => return ("fail", "timeout", part.answer.clone().err().unwrap_or_default()),
+        Status::Error => return ("fail", "error", part.answer.clone().err().unwrap_or_default()),
+        Status::Ok => {}
+    }
+    match context.answers.check(day, part.part, &part.answer) {
+        Verdict::Pass => ("pass", "pass", String::new()),
+        Verdict::Fail { expected, .. } => ("fail", "fail", format!("expected {}", expected)),
+        Verdict::Missing => {
+            let answer = part.answer.as_ref().ok();
+            match answer.and_then(|answer| context.ledger.known_wrong(day, part.part, answer)) {
+                Some(reason) => ("fail", "fail", reason),
+                None => ("unknown", "unverified", String::new()),
+            }
+        }
+    }
+}
+
+/// A self-contained HTML page showing `results`: each part's answer, how long it took and whether
+/// it's right, with a sparkline of each day's bench times over recent commits.
+pub fn render(results: &[DayResult], context: &Context) -> String {
+    let year = registry::all().next().map_or(2022, |solution| solution.year());
+    let stars = results
+        .iter()
+        .flat_map(|result| result.parts.iter().map(move |part| check(result.day, part, context).0))
+        .filter(|class| *class == "pass")
+        .count();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Advent of Code {year}</title>\n\
+         <style>\n{STYLE}</style>\n</head>\n<body>\n<h1>Advent of Code {year}</h1>\n\
+         <p class=\"meta\">{stars} star{s} &middot; commit {commit} &middot; {date}</p>\n",
+        year = year,
+        stars = stars,
+        s = if stars == 1 { "" } else { "s" },
+        commit = escape(context.commit),
+        date = format_date(context.time),
+        STYLE = STYLE,
+    );
+    html.push_str(
+        "<table>\n<thead><tr><th>Day</th><th>Puzzle</th><th>Part</th><th>Answer</th><th>Status</th>\
+         <th>Time</th><th>History</th></tr></thead>\n<tbody>\n",
+    );
+    for result in results {
+        render_day(&mut html, result, context);
+    }
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    html
+}
+
+fn render_day(html: &mut String, result: &DayResult, context: &Context) {
+    let title = match (result.title, result.variant) {
+        (Some(title), Some(variant)) => format!("{} ({})", escape(title), escape(variant)),
+        (Some(title), None) => escape(title),
+        (None, _) => String::new(),
+    };
+    if !result.is_implemented() || result.parts.is_empty() {
+        let _ = writeln!(
+            html,
+            "<tr class=\"day\"><td class=\"num\">{}</td><td>{}</td>\
+             <td colspan=\"5\" class=\"todo\">not started</td></tr>",
+            result.day, title
+        );
+        return;
+    }
+
+    let rows = result.parts.len();
+    for (i, part) in result.parts.iter().enumerate() {
+        let (class, label, note) = check(result.day, part, context);
+        html.push_str(if i == 0 { "<tr class=\"day\">" } else { "<tr>" });
+        if i == 0 {
+            let _ = write!(
+                html,
+                "<td class=\"num\" rowspan=\"{rows}\">{}</td><td rowspan=\"{rows}\">{}</td>",
+                result.day,
+                title,
+                rows = rows
+            );
+        }
+        let note = match note.as_str() {
+            "" => String::new(),
+            note => format!("<div class=\"note\">{}</div>", escape(note)),
+        };
+        let _ = write!(
+            html,
+            "<td class=\"num\">{}</td><td>{}</td><td class=\"{}\">{}{}</td><td class=\"num\">{}</td>",
+            part.part,
+            render_answer(&part.answer),
+            class,
+            label,
+            note,
+            format_duration(part.elapsed)
+        );
+        if i == 0 {
+            let _ = write!(html, "<td rowspan=\"{}\">{}</td>", rows, render_trend(result, context.history));
+        }
+        html.push_str("</tr>\n");
+    }
+}
+
+// An answer, with pictures (and any other answer that spans lines) in a monospace block
+fn render_answer(answer: &Result<Answer, String>) -> String {
+    match answer {
+        Ok(answer @ Answer::Grid(rows)) => {
+            let letters = answer.ocr().map(|letters| format!("<div>{}</div>", escape(&letters))).unwrap_or_default();
+            format!("{}<pre class=\"picture\">{}</pre>", letters, escape(&rows.join("\n")))
+        }
+        Ok(Answer::Unsolved) | Err(_) => String::new(),
+        Ok(answer) => match answer.to_string() {
+            text if text.contains('\n') => format!("<pre class=\"picture\">{}</pre>", escape(&text)),
+            text => format!("<code>{}</code>", escape(&text)),
+        },
+    }
+}
+
+// A sparkline of the day's bench times at recent commits, and the latest of them
+fn render_trend(result: &DayResult, history: &[Record]) -> String {
+    let variant = result.variant.or_else(|| registry::variants(result.day).first().map(|v| v.name));
+    let trend = match variant {
+        Some(variant) => history::day_trend(history, result.day, variant, "real", TREND_LENGTH),
+        None => Vec::new(),
+    };
+    match trend.last() {
+        Some(latest) => format!("{} <span class=\"note\">{}</span>", sparkline_svg(&trend), format_duration(*latest)),
+        None => "<span class=\"note\">not benched</span>".to_owned(),
+    }
+}
+
+/// `values` drawn as a small SVG line, scaled to fit between the lowest and the highest.
+pub fn sparkline_svg(values: &[Duration]) -> String {
+    const WIDTH: f64 = 120.0;
+    const HEIGHT: f64 = 24.0;
+    let secs: Vec<f64> = values.iter().map(Duration::as_secs_f64).collect();
+    let low = secs.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = secs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = if high > low { high - low } else { 1.0 };
+    let step = if secs.len() > 1 { WIDTH / (secs.len() - 1) as f64 } else { 0.0 };
+
+    let points: Vec<String> = secs
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("{:.1},{:.1}", i as f64 * step, HEIGHT - 2.0 - (s - low) / range * (HEIGHT - 4.0)))
+        .collect();
+    let last = points.last().cloned().unwrap_or_default();
+    let (x, y) = last.split_once(',').unwrap_or(("0", "0"));
+    format!(
+        "<svg class=\"spark\" width=\"{w}\" height=\"{h}\" viewBox=\"-2 0 {vw} {h}\"><polyline points=\"{}\"/>\
+         <circle cx=\"{}\" cy=\"{}\" r=\"2\"/></svg>",
+        points.join(" "),
+        x,
+        y,
+        w = WIDTH + 4.0,
+        vw = WIDTH + 4.0,
+        h = HEIGHT,
+    )
+}
+
+/// `text` with the characters that mean something in HTML escaped.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A time in seconds since the Unix epoch, as a UTC date and time like `2022-12-25 06:00 UTC`.
+pub fn format_date(time: u64) -> String {
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let days = (time / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    let minutes = time % 86400 / 60;
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, minutes / 60, minutes % 60)
+}
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0.2em; }
+.meta, .note { color: #666; font-size: 0.9em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3em 0.8em; text-align: left; vertical-align: top; }
+th { border-bottom: 2px solid #ccc; }
+tr.day td { border-top: 1px solid #ddd; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+code, pre { font-family: ui-monospace, monospace; }
+pre.picture { margin: 0.2em 0; line-height: 1; letter-spacing: 0.1em; }
+.pass { color: #176f2c; font-weight: bold; }
+.fail { color: #b3261e; font-weight: bold; }
+.unknown { color: #8a6d00; }
+.todo { color: #888; }
+svg.spark polyline { fill: none; stroke: #3366cc; stroke-width: 1.5; }
+svg.spark circle { fill: #3366cc; }
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_report_helpers() {
+        assert_eq!(escape("<a href=\"x\">&'</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;");
+        assert_eq!(format_date(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_date(1671948000), "2022-12-25 06:00 UTC");
+        let svg = sparkline_svg(&[Duration::from_millis(3), Duration::from_millis(1)]);
+        assert!(svg.contains("points=\"0.0,2.0 120.0,22.0\""), "{}", svg);
+    }
+
+    #[test]
+    fn check_report_html() {
+        let answers = Answers::parse(r#"{"10": {"part1": "13140"}}"#).unwrap();
+        let ledger = Ledger::default();
+        let context = Context {
+            answers: &answers,
+            ledger: &ledger,
+            history: &[],
+            commit: "0123abcd",
+            time: 1671948000,
+        };
+        let part = |part, answer| PartResult {
+            part,
+            status: Status::Ok,
+            answer: Ok(answer),
+            elapsed: Duration::from_micros(250),
+            alloc: None,
+        };
+        let result = DayResult {
+            day: 10,
+            title: Some("Cathode-Ray Tube"),
+            variant: None,
+            input_hash: None,
+            error: None,
+            parts: vec![part(1, Answer::Int(13141)), part(2, Answer::grid(["#..#", "<..>"]))],
+            elapsed: Duration::from_micros(500),
+            alloc: None,
+        };
+
+        let html = render(&[result], &context);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("0 stars"));
+        assert!(html.contains("<td class=\"fail\">fail<div class=\"note\">expected 13140</div></td>"));
+        assert!(html.contains("<pre class=\"picture\">#..#\n&lt;..&gt;</pre>"));
+        assert!(html.contains("<td class=\"unknown\">unverified</td>"));
+        // Nothing is loaded from anywhere else
+        assert!(!html.contains("src=") && !html.contains("href="));
+    }
+}
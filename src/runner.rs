@@ -0,0 +1,353 @@
+use std::env;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aoc2022_rs_jtgs::answers::Expected;
+use aoc2022_rs_jtgs::error::SolveError;
+use aoc2022_rs_jtgs::helpers::{input_hash, load_named_input, normalize_input};
+use aoc2022_rs_jtgs::registry;
+use aoc2022_rs_jtgs::solution::{Answer, Params, Variant};
+
+use crate::alloc_stats;
+use crate::cli::{self, InputSource, RunOptions, Variants};
+use crate::output::{DayResult, PartResult, Status};
+
+/// Run each of `options.days`, using up to `options.jobs` threads, and call `each` with each
+/// day's result, in day order, as soon as it and all the days before it have finished.  With
+/// `--all-variants`, each variant of a day has a result of its own.
+pub fn run_days(options: &RunOptions, mut each: impl FnMut(&DayResult)) -> Vec<DayResult> {
+    let jobs: Vec<(usize, RunOptions)> = options
+        .days
+        .iter()
+        .flat_map(|&day| each_variant(day, options).into_iter().map(move |run| (day, run)))
+        .collect();
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let mut results: Vec<Option<DayResult>> = vec![None; jobs.len()];
+
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.min(jobs.len()) {
+            let (next, sender, jobs) = (&next, sender.clone(), &jobs);
+            scope.spawn(move || loop {
+                let ix = next.fetch_add(1, Ordering::Relaxed);
+                let Some((day, run)) = jobs.get(ix) else {
+                    break;
+                };
+                if sender.send((ix, run_day(*day, run))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut printed = 0;
+        for (ix, result) in receiver {
+            results[ix] = Some(result);
+            while let Some(Some(result)) = results.get(printed) {
+                each(result);
+                printed += 1;
+            }
+        }
+    });
+
+    results.into_iter().flatten().collect()
+}
+
+/// The options for running each variant of `day` that `options` selects, one variant at a time.
+pub fn each_variant(day: usize, options: &RunOptions) -> Vec<RunOptions> {
+    match options.variant {
+        Variants::All if registry::variants(day).len() > 1 => registry::variants(day)
+            .iter()
+            .map(|variant| RunOptions {
+                variant: Variants::Named(variant.name.to_owned()),
+                ..options.clone()
+            })
+            .collect(),
+        _ => vec![options.clone()],
+    }
+}
+
+/// The variants of `day`'s solution that `selected` picks out, or why there are none: an empty
+/// list means there's no solution at all.
+pub fn select_variants(day: usize, selected: &Variants) -> Result<Vec<Variant>, String> {
+    let variants = registry::variants(day);
+    match selected {
+        Variants::Default => Ok(variants.iter().take(1).copied().collect()),
+        Variants::All => Ok(variants.to_vec()),
+        Variants::Named(_) if variants.is_empty() => Ok(Vec::new()),
+        Variants::Named(name) => match variants.iter().find(|variant| variant.name == name) {
+            Some(variant) => Ok(vec![*variant]),
+            None => {
+                let names: Vec<&str> = variants.iter().map(|variant| variant.name).collect();
+                Err(format!("day {} has no variant `{}` (it has {})", day, name, names.join(", ")))
+            }
+        },
+    }
+}
+
+/// The name to show for `variant` of `day`: only days with more than one variant need one.
+pub fn variant_label(day: usize, variant: &Variant) -> Option<&'static str> {
+    (registry::variants(day).len() > 1).then_some(variant.name)
+}
+
+/// Run one day, catching any panics, so that they are reported like any other error.  If the day
+/// has a time limit, it runs in a child process, which is killed if the limit is reached.
+pub fn run_day(day: usize, options: &RunOptions) -> DayResult {
+    match options.timeout {
+        Some(limit) => run_day_in_child(day, options, limit),
+        None => run_day_here(day, options),
+    }
+}
+
+// A result with no parts yet, for the variant that `options` selects
+fn new_result(day: usize, options: &RunOptions) -> DayResult {
+    let variant = select_variants(day, &options.variant).ok().and_then(|variants| variants.first().copied());
+    DayResult {
+        day,
+        title: registry::get(day).map(|solution| solution.title()),
+        variant: variant.and_then(|variant| variant_label(day, &variant)),
+        input_hash: None,
+        error: None,
+        parts: Vec::new(),
+        elapsed: Duration::ZERO,
+        alloc: None,
+    }
+}
+
+// Fail every part that `options` asks for in the same way, because the day couldn't be run
+fn failed(mut result: DayResult, options: &RunOptions, status: Status, error: String) -> DayResult {
+    result.parts = (1..=2)
+        .filter(|&part| options.parts.includes(part))
+        .map(|part| PartResult {
+            part,
+            status,
+            answer: Err(error.clone()),
+            elapsed: Duration::ZERO,
+            alloc: None,
+        })
+        .collect();
+    result.error = Some(error);
+    result
+}
+
+/// The path to this program, as it was the first time this was called.  (Once the program has been
+/// rebuilt, as `watch` does while running, the OS may no longer say where it is.)
+pub fn program_path() -> Result<PathBuf, String> {
+    static PATH: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+    PATH.get_or_init(|| env::current_exe().map_err(|e| e.to_string())).clone()
+}
+
+fn run_day_in_child(day: usize, options: &RunOptions, limit: Duration) -> DayResult {
+    let result = new_result(day, options);
+    let child = program_path().and_then(|exe| {
+        Command::new(exe)
+            .args(cli::child_args(day, options))
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())
+    });
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return failed(result, options, Status::Error, format!("can't start a child process ({})", e)),
+    };
+
+    // Read the output as it comes, so the child can't block on a full pipe
+    let mut stdout = child.stdout.take().expect("child's stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let started = Instant::now();
+    let exit_status = loop {
+        match child.try_wait() {
+            Ok(Some(exit_status)) => break exit_status,
+            Ok(None) if started.elapsed() < limit => thread::sleep(POLL_INTERVAL),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                let mut result = failed(result, options, Status::Timeout, format!("timed out after {:?}", limit));
+                result.elapsed = started.elapsed();
+                return result;
+            }
+            Err(e) => return failed(result, options, Status::Error, format!("lost the child process ({})", e)),
+        }
+    };
+
+    // The result is on the last line; anything before that is the solution's own output
+    let output = reader.join().ok().and_then(Result::ok).unwrap_or_default();
+    output
+        .lines()
+        .last()
+        .and_then(|line| serde_json::from_str(line).ok())
+        .and_then(|json| DayResult::from_json(&json, result.title, result.variant))
+        .unwrap_or_else(|| failed(result, options, Status::Error, format!("the child process failed ({})", exit_status)))
+}
+
+// How often to check whether a child process has finished
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+fn run_day_here(day: usize, options: &RunOptions) -> DayResult {
+    let mut result = new_result(day, options);
+    let solution = match select_variants(day, &options.variant).map(|variants| variants.first().copied()) {
+        Ok(Some(variant)) => variant.solver,
+        Ok(None) => return failed(result, options, Status::NotImplemented, "no solution module".to_owned()),
+        Err(e) => return failed(result, options, Status::Error, e),
+    };
+
+    let (input_lines, params) = match read_input(day, &options.input) {
+        Ok(input) => input,
+        Err(e) => return failed(result, options, Status::Error, e),
+    };
+    result.input_hash = Some(input_hash(&input_lines));
+
+    let start_time = Instant::now();
+    let (parts, alloc) = alloc_stats::measure(|| {
+        let parsed = catch_panic(|| solution.parse_any_with(&input_lines, &params))?;
+        let parts = (1..=2).filter(|&part| options.parts.includes(part)).map(|part| {
+            let part_start = Instant::now();
+            let (answer, alloc) = alloc_stats::measure(|| {
+                catch_panic(|| match part {
+                    1 => solution.part1_any(parsed.as_ref()),
+                    _ => solution.part2_any(parsed.as_ref()),
+                })
+            });
+            let elapsed = part_start.elapsed();
+            let status = match answer {
+                Ok(Answer::Unsolved) => Status::Unsolved,
+                Ok(_) => Status::Ok,
+                Err(_) => Status::Error,
+            };
+            PartResult {
+                part,
+                status,
+                answer,
+                elapsed,
+                alloc,
+            }
+        });
+        Ok::<_, String>(parts.collect())
+    });
+    let elapsed = start_time.elapsed();
+    match parts {
+        Ok(parts) => {
+            result.parts = parts;
+            result.elapsed = elapsed;
+            result.alloc = alloc;
+            result
+        }
+        Err(e) => failed(result, options, Status::Error, format!("bad input, {}", e)),
+    }
+}
+
+// Call `f`, turning a panic into an error like any other (the panic message still goes to stderr)
+fn catch_panic<T>(f: impl FnOnce() -> Result<T, SolveError>) -> Result<T, String> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(payload) => {
+            let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+                (Some(message), _) => message,
+                (_, Some(message)) => message.as_str(),
+                _ => "(no message)",
+            };
+            Err(format!("panicked: {}", message))
+        }
+    }
+}
+
+// The input, and the parameters that go with it (from its .expected file, for a named input)
+pub fn read_input(day: usize, source: &InputSource) -> Result<(String, Params), String> {
+    match source {
+        InputSource::Named(name) => {
+            let input = load_named_input(day, name)?;
+            let params = Expected::load(day, name)?.map(|expected| expected.params).unwrap_or_default();
+            Ok((input, params))
+        }
+        InputSource::File(path) => std::fs::read_to_string(path)
+            .map(|input| (normalize_input(&input), Params::new()))
+            .map_err(|e| format!("can't read {} ({})", path.display(), e)),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("can't read stdin ({})", e))?;
+            Ok((normalize_input(&input), Params::new()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Parts, Variants};
+    use crate::output::Format;
+
+    #[test]
+    fn check_catch_panic() {
+        let result: Result<(), String> = catch_panic(|| panic!("ran out of bottom!"));
+        assert_eq!(result, Err("panicked: ran out of bottom!".to_owned()));
+        let result: Result<u8, String> = catch_panic(|| Err(SolveError::new("too many steps")));
+        assert_eq!(result, Err("too many steps".to_owned()));
+    }
+
+    #[test]
+    fn check_run_days_in_order() {
+        let options = RunOptions {
+            days: vec![3, 1, 2, 4],
+            parts: Parts::One,
+            input: InputSource::Named("example1".to_owned()),
+            variant: Variants::Default,
+            release_check: false,
+            format: Format::Text,
+            jobs: 3,
+            timeout: None,
+        };
+        let mut seen = Vec::new();
+        let results = run_days(&options, |result| seen.push(result.day));
+        assert_eq!(seen, [3, 1, 2, 4]);
+        let answers: Vec<_> = results.iter().map(|result| result.parts[0].answer.clone()).collect();
+        assert_eq!(answers, [Ok(Answer::Int(157)), Ok(Answer::Int(24000)), Ok(Answer::Int(15)), Ok(Answer::Int(2))]);
+
+        // Each variant of day 2 gets a result of its own
+        let options = RunOptions {
+            days: vec![1, 2],
+            variant: Variants::All,
+            ..options
+        };
+        let results = run_days(&options, |_| {});
+        let variants: Vec<_> = results.iter().map(|result| (result.day, result.variant)).collect();
+        assert_eq!(variants, [(1, None), (2, Some("enum")), (2, Some("table"))]);
+        assert!(results.iter().all(|result| result.error.is_none()));
+
+        let options = RunOptions {
+            variant: Variants::Named("quantum".to_owned()),
+            ..options
+        };
+        let result = run_day(2, &options);
+        assert_eq!(result.error.as_deref(), Some("day 2 has no variant `quantum` (it has enum, table)"));
+    }
+
+    #[test]
+    fn check_run_day_not_implemented() {
+        let options = RunOptions {
+            days: vec![25],
+            parts: Parts::Both,
+            input: InputSource::real(),
+            variant: Variants::Default,
+            release_check: false,
+            format: Format::Text,
+            jobs: 1,
+            timeout: None,
+        };
+        let result = run_day(25, &options);
+        assert!(!result.is_implemented());
+        let statuses: Vec<Status> = result.parts.iter().map(|part| part.status).collect();
+        assert_eq!(statuses, [Status::NotImplemented, Status::NotImplemented]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aoc2022_rs_jtgs::helpers::input_dir;
+
+/// The template for a new day's module, relative to the repository root.  `{{day}}` in it becomes
+/// the day number, `{{padded_day}}` the day number with two digits (as in `Day08`), and `{{year}}`
+/// the year.
+pub const TEMPLATE_PATH: &str = "templates/day.rs";
+
+/// The input files a new day starts with.  They're left empty, so the `.expected` file has no
+/// answers (and so no tests) until it's filled in.
+const NEW_INPUTS: [&str; 3] = ["real.txt", "example1.txt", "example1.expected"];
+
+/// Start a new day in the repository at `root`: write `src/dayNN.rs` from the template (build.rs
+/// then finds it and registers its solution), and create the day's input files in its directory
+/// under `inputs_root()`.  Refuses to overwrite an existing module, and leaves any existing inputs
+/// alone.  Returns the files created.
+pub fn new_day(root: &Path, day: usize, year: u16) -> Result<Vec<PathBuf>, String> {
+    create_day(root, &input_dir(day), day, year)
+}
+
+/// `new_day`, with the day's inputs going in `input_dir`.
+fn create_day(root: &Path, input_dir: &Path, day: usize, year: u16) -> Result<Vec<PathBuf>, String> {
+    let module_path = root.join(format!("src/day{:02}.rs", day));
+    if module_path.exists() {
+        return Err(format!("{} already exists, so won't overwrite it", module_path.display()));
+    }
+
+    let template_path = root.join(TEMPLATE_PATH);
+    let template = fs::read_to_string(&template_path)
+        .map_err(|e| format!("can't read the template {} ({})", template_path.display(), e))?;
+    let source = template
+        .replace("{{day}}", &day.to_string())
+        .replace("{{padded_day}}", &format!("{:02}", day))
+        .replace("{{year}}", &year.to_string());
+
+    let write = |path: &Path, contents: &str| {
+        fs::write(path, contents).map_err(|e| format!("can't write {} ({})", path.display(), e))
+    };
+    write(&module_path, &source)?;
+    let mut created = vec![module_path];
+
+    fs::create_dir_all(input_dir).map_err(|e| format!("can't create {} ({})", input_dir.display(), e))?;
+    for name in NEW_INPUTS {
+        let path = input_dir.join(name);
+        if !path.exists() {
+            write(&path, "")?;
+            created.push(path);
+        }
+    }
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_scaffold_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("templates")).unwrap();
+        fs::copy(TEMPLATE_PATH, root.join(TEMPLATE_PATH)).unwrap();
+        fs::create_dir_all(root.join("inputs/03")).unwrap();
+        fs::write(root.join("inputs/03/real.txt"), "my input\n").unwrap();
+
+        // real.txt is already there, so it's left alone
+        let created = create_day(&root, &root.join("inputs/03"), 3, 2023).unwrap();
+        assert_eq!(created.len(), 3, "{:?}", created);
+        let source = fs::read_to_string(root.join("src/day03.rs")).unwrap();
+        assert!(source.contains("crate::register!(Day03);") && source.contains("        2023\n"), "{}", source);
+        assert_eq!(fs::read_to_string(root.join("inputs/03/real.txt")).unwrap(), "my input\n");
+        assert_eq!(fs::read_to_string(root.join("inputs/03/example1.expected")).unwrap(), "");
+
+        // The second time, there's work to lose
+        assert!(create_day(&root, &root.join("inputs/03"), 3, 2023).unwrap_err().contains("already exists"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
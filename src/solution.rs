@@ -0,0 +1,144 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+
+pub use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+
+/// Parameters that go with a particular input, such as day 15's `target_y`, which is different for
+/// the example.  These are read from the input's `.expected` file.
+pub type Params = BTreeMap<String, String>;
+
+/// A solution to a single day's puzzle.
+///
+/// The input is parsed once, and the parsed form is then handed to each part in turn, so the
+/// parse cost and the cost of each part can be measured (and reported) separately.
+pub trait Solution {
+    /// The parsed form of the puzzle input, shared by both parts.
+    type Input: 'static;
+
+    /// Day of the month the puzzle was released (day 0 is the installation check).
+    fn day(&self) -> usize;
+
+    /// The puzzle's title, as shown on the puzzle page.
+    fn title(&self) -> &'static str;
+
+    /// Year of the Advent of Code event the puzzle belongs to.
+    fn year(&self) -> u16 {
+        2022
+    }
+
+    /// Parse the whole puzzle input.  Errors are relative to `input`.
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Parse the whole puzzle input, given the parameters that go with it.  Only days whose
+    /// examples differ from the real puzzle in more than the input need to override this.
+    fn parse_with(&self, input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        let _ = params;
+        self.parse(input)
+    }
+
+    /// Solve part 1, or return `Answer::Unsolved` if it hasn't been solved yet.
+    fn part1(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    /// Solve part 2, or return `Answer::Unsolved` if it hasn't been solved yet.
+    fn part2(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    /// Parse the input and solve both parts, panicking on any error.
+    #[cfg(test)]
+    fn solve(&self, input: &str) -> (Answer, Answer) {
+        let parsed = self
+            .parse(input)
+            .unwrap_or_else(|e| panic!("{}", SolveError::parse(input, e)));
+        let check = |answer: Result<Answer, SolveError>| answer.unwrap_or_else(|e| panic!("{}", e));
+        (check(self.part1(&parsed)), check(self.part2(&parsed)))
+    }
+}
+
+/// The name of a day's solution when it's the only one.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// One of the ways a day is solved, under its name.
+#[derive(Clone, Copy)]
+pub struct Variant {
+    pub name: &'static str,
+    pub solver: &'static dyn Solver,
+}
+
+/// Register the solutions in this day's module, so that the runner can find them.  Every `dayNN`
+/// module does this once, usually for a single solution:
+///
+/// ```ignore
+/// pub struct Day08;
+///
+/// crate::register!(Day08);
+/// ```
+///
+/// A day solved more than one way registers each way under a name.  The first is the one that's
+/// used unless another is asked for; the rest have to give the same answers.
+///
+/// ```ignore
+/// crate::register!("enum" => Day02, "table" => Day02Table);
+/// ```
+#[macro_export]
+macro_rules! register {
+    ($solution:expr) => {
+        $crate::register!($crate::solution::DEFAULT_VARIANT => $solution);
+    };
+    ($($name:expr => $solution:expr),+ $(,)?) => {
+        /// This day's solutions, as listed in the registry.
+        pub const VARIANTS: &[$crate::solution::Variant] = &[
+            $($crate::solution::Variant { name: $name, solver: &$solution }),+
+        ];
+    };
+}
+
+/// Type-erased view of a `Solution`, so that solutions with different `Input` types can live
+/// side by side in the registry.
+pub trait Solver: Sync {
+    fn day(&self) -> usize;
+    fn title(&self) -> &'static str;
+    fn year(&self) -> u16;
+    fn parse_any_with(&self, input: &str, params: &Params) -> Result<Box<dyn Any>, SolveError>;
+    fn part1_any(&self, parsed: &dyn Any) -> Result<Answer, SolveError>;
+    fn part2_any(&self, parsed: &dyn Any) -> Result<Answer, SolveError>;
+
+    /// Parse input that has no parameters.
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, SolveError> {
+        self.parse_any_with(input, &Params::new())
+    }
+}
+
+impl<S: Solution + Sync> Solver for S {
+    fn day(&self) -> usize {
+        Solution::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Solution::title(self)
+    }
+
+    fn year(&self) -> u16 {
+        Solution::year(self)
+    }
+
+    fn parse_any_with(&self, input: &str, params: &Params) -> Result<Box<dyn Any>, SolveError> {
+        match self.parse_with(input, params) {
+            Ok(parsed) => Ok(Box::new(parsed)),
+            Err(e) => Err(SolveError::parse(input, e)),
+        }
+    }
+
+    fn part1_any(&self, parsed: &dyn Any) -> Result<Answer, SolveError> {
+        self.part1(downcast::<S>(parsed))
+    }
+
+    fn part2_any(&self, parsed: &dyn Any) -> Result<Answer, SolveError> {
+        self.part2(downcast::<S>(parsed))
+    }
+}
+
+fn downcast<S: Solution>(parsed: &dyn Any) -> &S::Input {
+    parsed
+        .downcast_ref::<S::Input>()
+        .expect("parsed input passed to a different day's solution")
+}
@@ -0,0 +1,100 @@
+use aoc2022_rs_jtgs::answers::Answers;
+use aoc2022_rs_jtgs::helpers::{input_names, REAL_INPUT};
+
+use crate::cli::{InputSource, Parts, RunOptions, Variants};
+use crate::output::{Format, Status};
+use crate::runner::run_day;
+
+/// How far along one part of one day is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// There's no module for the day.
+    NotImplemented,
+    /// The part says it hasn't been solved yet.
+    Unsolved,
+    /// The part fails, or the day's input can't be parsed.
+    Failing,
+    /// The part gives an answer, but none has been accepted yet.
+    Solved,
+    /// The part gives an answer, and the answer for the real input is in the answers file.
+    Accepted,
+}
+
+impl Progress {
+    fn symbol(self) -> char {
+        match self {
+            Progress::NotImplemented => '.',
+            Progress::Unsolved => '-',
+            Progress::Failing => '!',
+            Progress::Solved => '+',
+            Progress::Accepted => '*',
+        }
+    }
+}
+
+/// The progress of each part of `day`.  Each part is run to see whether it's been solved, on the
+/// day's first example if it has one (since that's quick), or its real input if not.
+pub fn day_progress(day: usize, answers: &Answers) -> [Progress; 2] {
+    let input = input_names(day)
+        .into_iter()
+        .find(|name| name != REAL_INPUT)
+        .unwrap_or_else(|| REAL_INPUT.to_owned());
+    let options = RunOptions {
+        days: vec![day],
+        parts: Parts::Both,
+        input: InputSource::Named(input),
+        variant: Variants::Default,
+        release_check: false,
+        format: Format::Text,
+        jobs: 1,
+        timeout: None,
+    };
+    let result = run_day(day, &options);
+
+    [1, 2].map(|part| match result.parts.iter().find(|p| p.part == part).map(|p| p.status) {
+        Some(Status::Ok) if answers.get(day, part).is_some() => Progress::Accepted,
+        Some(Status::Ok) => Progress::Solved,
+        Some(Status::Unsolved) => Progress::Unsolved,
+        Some(Status::NotImplemented) => Progress::NotImplemented,
+        _ => Progress::Failing,
+    })
+}
+
+/// Print a grid of every day's progress, with a row for each part.
+pub fn print_status(answers: &Answers) {
+    let days: Vec<(usize, [Progress; 2])> = (1..=25).map(|day| (day, day_progress(day, answers))).collect();
+
+    print!("      ");
+    for (day, _) in &days {
+        print!("{:>3}", day);
+    }
+    println!();
+    for part in [1, 2] {
+        print!("Part {}", part);
+        for (_, progress) in &days {
+            print!("{:>3}", progress[part - 1].symbol());
+        }
+        println!();
+    }
+
+    let stars = days
+        .iter()
+        .flat_map(|(_, progress)| progress)
+        .filter(|&&progress| progress == Progress::Accepted)
+        .count();
+    println!("\n* accepted  + solved  - unsolved  ! failing  . no solution module");
+    println!("{} of 50 stars", stars);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_status_day_progress() {
+        let answers = Answers::parse(r#"{"1": {"part1": "71506"}, "17": {"part1": "3232"}}"#).unwrap();
+        assert_eq!(day_progress(1, &answers), [Progress::Accepted, Progress::Solved]);
+        assert_eq!(day_progress(17, &answers), [Progress::Accepted, Progress::Unsolved]);
+        assert_eq!(day_progress(25, &answers), [Progress::NotImplemented, Progress::NotImplemented]);
+    }
+}
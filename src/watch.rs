@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use aoc2022_rs_jtgs::answers::{answers_path, Answers, Expected};
+use aoc2022_rs_jtgs::helpers::{input_dir, input_names, REAL_INPUT};
+use aoc2022_rs_jtgs::solution::Answer;
+
+use crate::cli::{InputSource, Parts, RunOptions, Variants, WatchOptions};
+use crate::ledger::Ledger;
+use crate::output::{DayResult, Format, PartResult, Status};
+use crate::runner::{program_path, run_day};
+
+/// How often to look for changes.  Polling, rather than asking the OS to say when files change,
+/// works the same everywhere, including in containers with mounted source.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// When each watched file was last changed (and how big it is, in case the clock is coarse), or
+/// `None` if it doesn't exist.
+type Snapshot = BTreeMap<PathBuf, Option<(SystemTime, u64)>>;
+
+/// The files that `day` depends on: its module, its inputs and `.expected` files, and the answers
+/// file.
+pub fn watched_files(root: &Path, day: usize) -> Vec<PathBuf> {
+    let mut files = vec![root.join(format!("src/day{:02}.rs", day))];
+    let mut inputs: Vec<PathBuf> = fs::read_dir(input_dir(day))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    inputs.sort();
+    files.extend(inputs);
+    files.push(answers_path());
+    files
+}
+
+fn snapshot(files: &[PathBuf]) -> Snapshot {
+    files
+        .iter()
+        .map(|path| {
+            let stamp = fs::metadata(path).ok().and_then(|metadata| Some((metadata.modified().ok()?, metadata.len())));
+            (path.clone(), stamp)
+        })
+        .collect()
+}
+
+/// The files that differ between two snapshots, including any that appeared or disappeared.
+pub fn changed_files(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = after
+        .iter()
+        .filter(|(path, stamp)| before.get(*path) != Some(stamp))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(before.keys().filter(|path| !after.contains_key(*path)).cloned());
+    changed
+}
+
+/// Watch `options.day`'s files, and each time any of them changes, rebuild and re-run the day on
+/// each of its inputs.  Runs until it's killed.
+pub fn watch(root: &Path, options: &WatchOptions) {
+    let day = options.day;
+    let mut previous: BTreeMap<(String, usize), Answer> = BTreeMap::new();
+    let mut seen = Snapshot::new();
+    // Find this program now, while it's still where it started
+    if let Err(e) = program_path() {
+        eprintln!("error: can't find this program to rebuild it ({})", e);
+        return;
+    }
+
+    println!("Watching day {} (press Ctrl-C to stop)", day);
+    loop {
+        let mut current = snapshot(&watched_files(root, day));
+        if current == seen {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+
+        // Wait for an editor to finish saving before starting
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let settled = snapshot(&watched_files(root, day));
+            if settled == current {
+                break;
+            }
+            current = settled;
+        }
+
+        let changed = changed_files(&seen, &current);
+        let names: Vec<String> = changed
+            .iter()
+            .map(|path| path.strip_prefix(root).unwrap_or(path).display().to_string())
+            .collect();
+        if seen.is_empty() {
+            println!("\n---- day {} ----", day);
+        } else {
+            println!("\n---- {} changed ----", names.join(", "));
+        }
+        seen = current;
+
+        if !build(root) {
+            println!("build failed");
+            continue;
+        }
+        check_day(day, options.timeout, &mut previous);
+    }
+}
+
+// Rebuild the program, as this one was built and in the same place, so that the child processes
+// that run each input run the new code
+fn build(root: &Path) -> bool {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command.current_dir(root).args(["build", "--quiet"]);
+    // This program is <target dir>/<profile>/<name>
+    if let Some(target_dir) = program_path().ok().and_then(|exe| Some(exe.parent()?.parent()?.to_owned())) {
+        command.arg("--target-dir").arg(target_dir);
+    }
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    let features: Vec<&str> = [
+        ("embed-inputs", cfg!(feature = "embed-inputs")),
+        ("alloc-stats", cfg!(feature = "alloc-stats")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect();
+    if !features.is_empty() {
+        command.args(["--features", &features.join(",")]);
+    }
+    command.status().is_ok_and(|status| status.success())
+}
+
+// Run `day` on each of its inputs, each in a child process (so with the code just built), and
+// print a line for each input
+fn check_day(day: usize, timeout: Duration, previous: &mut BTreeMap<(String, usize), Answer>) {
+    let mut answers = Answers::load().unwrap_or_default();
+    if let Ok(ledger) = Ledger::load() {
+        ledger.accept_correct(&mut answers);
+    }
+
+    // The examples first, since they're quicker and more likely to show what's wrong
+    let mut names = input_names(day);
+    if names.first().is_some_and(|name| name == REAL_INPUT) {
+        names.rotate_left(1);
+    }
+
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    for name in names {
+        let example_answers = match name.as_str() {
+            REAL_INPUT => None,
+            _ => match Expected::load(day, &name) {
+                Ok(expected) => Some(expected.map(|expected| expected.answers).unwrap_or_default()),
+                Err(e) => {
+                    println!("{:<width$}  {}", name, e, width = width);
+                    continue;
+                }
+            },
+        };
+        let options = RunOptions {
+            days: vec![day],
+            parts: Parts::Both,
+            input: InputSource::Named(name.clone()),
+            variant: Variants::Default,
+            release_check: false,
+            format: Format::Text,
+            jobs: 1,
+            timeout: Some(timeout),
+        };
+        let result = run_day(day, &options);
+        let expected = example_answers.as_ref().unwrap_or(&answers);
+        println!("{:<width$}  {}", name, summarise(&result, expected, &name, previous), width = width);
+    }
+}
+
+// A line saying how each part went, with the answers that differ from what was expected, or from
+// what the part gave last time
+fn summarise(
+    result: &DayResult,
+    expected: &Answers,
+    name: &str,
+    previous: &mut BTreeMap<(String, usize), Answer>,
+) -> String {
+    if let Some(error) = &result.error {
+        return format!("ERROR ({})", error);
+    }
+
+    let parts: Vec<String> = result
+        .parts
+        .iter()
+        .map(|part| {
+            let was = match &part.answer {
+                Ok(answer) => previous.insert((name.to_owned(), part.part), answer.clone()),
+                Err(_) => previous.remove(&(name.to_owned(), part.part)),
+            };
+            format!("part {} {}", part.part, summarise_part(part, expected.get(result.day, part.part), was))
+        })
+        .collect();
+    parts.join("  ")
+}
+
+fn summarise_part(part: &PartResult, expected: Option<&Answer>, was: Option<Answer>) -> String {
+    let answer = match (&part.answer, part.status) {
+        (_, Status::Unsolved) => return "TODO".to_owned(),
+        (_, Status::Timeout) => return "TIMEOUT".to_owned(),
+        (Err(e), _) => return format!("ERROR ({})", e),
+        (Ok(answer), _) => answer,
+    };
+
+    let mut summary = match expected {
+        Some(expected) if expected == answer => format!("PASS {}", compact(answer)),
+        Some(expected) => format!("FAIL {} (expected {})", compact(answer), compact(expected)),
+        None => format!("NEW {}", compact(answer)),
+    };
+    if let Some(was) = was.filter(|was| was != answer) {
+        summary.push_str(&format!(" (was {})", compact(&was)));
+    }
+    summary
+}
+
+// An answer on one line: a picture that can't be read is too big to show
+fn compact(answer: &Answer) -> String {
+    match answer.to_string() {
+        text if text.contains('\n') => "(picture)".to_owned(),
+        text => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_watch_changes() {
+        let dir = env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("a.txt"), dir.join("b.txt"));
+        fs::write(&a, "1").unwrap();
+        let _ = fs::remove_file(&b);
+        let files = [a.clone(), b.clone()];
+
+        let before = snapshot(&files);
+        assert!(changed_files(&before, &snapshot(&files)).is_empty());
+        fs::write(&a, "12").unwrap();
+        fs::write(&b, "").unwrap();
+        assert_eq!(changed_files(&before, &snapshot(&files)), [a, b]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn check_watch_summary() {
+        let part = |answer: Answer| PartResult {
+            part: 1,
+            status: Status::Ok,
+            answer: Ok(answer),
+            elapsed: Duration::ZERO,
+            alloc: None,
+        };
+        assert_eq!(summarise_part(&part(Answer::Int(95437)), Some(&Answer::Int(95437)), None), "PASS 95437");
+        assert_eq!(
+            summarise_part(&part(Answer::Int(95436)), Some(&Answer::Int(95437)), Some(Answer::Int(95437))),
+            "FAIL 95436 (expected 95437) (was 95437)"
+        );
+        assert_eq!(summarise_part(&part(Answer::grid(["#.", ".#"])), None, None), "NEW (picture)");
+
+        let unsolved = PartResult {
+            status: Status::Unsolved,
+            ..part(Answer::Unsolved)
+        };
+        assert_eq!(summarise_part(&unsolved, None, None), "TODO");
+    }
+}
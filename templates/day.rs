@@ -0,0 +1,37 @@
+//! Day {{day}}: TODO: the puzzle's title.
+
+use crate::error::{ParseError, SolveError};
+use crate::solution::{Answer, Solution};
+
+pub struct Day{{padded_day}};
+
+crate::register!(Day{{padded_day}});
+
+impl Solution for Day{{padded_day}} {
+    // The input's lines, until there's something better to parse them into
+    type Input = Vec<String>;
+
+    fn day(&self) -> usize {
+        {{day}}
+    }
+
+    fn title(&self) -> &'static str {
+        "TODO: the puzzle's title"
+    }
+
+    fn year(&self) -> u16 {
+        {{year}}
+    }
+
+    fn parse(&self, input_lines: &str) -> Result<Self::Input, ParseError> {
+        Ok(input_lines.lines().map(str::to_owned).collect())
+    }
+
+    fn part1(&self, _lines: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part2(&self, _lines: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+}